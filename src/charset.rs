@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Secrets stored in a character set other than UTF-8, see
+//! [`KeyringProvider::with_charset`](crate::KeyringProvider::with_charset).
+
+use crate::error::{KeyringError, Result};
+
+/// The character set a secret's bytes are stored in.
+///
+/// ```rust
+/// # use figment_keyring::Charset;
+/// assert_eq!(Charset::for_label("windows-1252").unwrap().name(), "windows-1252");
+/// assert_eq!(Charset::for_label("utf-16le"), Some(Charset::UTF_16LE));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Charset(&'static encoding_rs::Encoding);
+
+impl Charset {
+    /// UTF-8, the default
+    pub const UTF_8: Charset = Charset(encoding_rs::UTF_8);
+    /// Little-endian UTF-16, as used by Windows
+    pub const UTF_16LE: Charset = Charset(encoding_rs::UTF_16LE);
+    /// Big-endian UTF-16
+    pub const UTF_16BE: Charset = Charset(encoding_rs::UTF_16BE);
+
+    /// Look a charset up by a WHATWG label such as `"windows-1252"` or
+    /// `"shift_jis"`.
+    pub fn for_label(label: &str) -> Option<Charset> {
+        encoding_rs::Encoding::for_label(label.as_bytes()).map(Charset)
+    }
+
+    /// The canonical name of the charset.
+    pub fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    /// Re-encode `secret` from this charset as UTF-8.
+    ///
+    /// A leading byte order mark for this charset is dropped. Malformed
+    /// input is an error rather than being replaced.
+    pub(crate) fn decode(&self, secret: Vec<u8>, name: &str) -> Result<Vec<u8>> {
+        if self.0 == encoding_rs::UTF_8 {
+            return Ok(secret);
+        }
+        match self.0.decode_with_bom_removal(&secret) {
+            (decoded, false) => Ok(decoded.into_owned().into_bytes()),
+            (_, true) => Err(KeyringError::ParseError(format!(
+                "secret '{}' is not valid {}",
+                name,
+                self.name()
+            ))),
+        }
+    }
+}
+
+impl Default for Charset {
+    fn default() -> Self {
+        Charset::UTF_8
+    }
+}
+
+impl From<&'static encoding_rs::Encoding> for Charset {
+    fn from(encoding: &'static encoding_rs::Encoding) -> Self {
+        Charset(encoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_utf16le() {
+        let secret = vec![0x73, 0x00, 0x33, 0x00, 0x63, 0x00, 0xe9, 0x00];
+        assert_eq!(
+            Charset::UTF_16LE.decode(secret, "key").unwrap(),
+            "s3cé".as_bytes()
+        );
+        let with_bom = vec![0xff, 0xfe, 0x73, 0x00];
+        assert_eq!(Charset::UTF_16LE.decode(with_bom, "key").unwrap(), b"s");
+    }
+
+    #[test]
+    fn test_decode_malformed() {
+        assert!(matches!(
+            Charset::UTF_16LE.decode(vec![0x00, 0xd8], "api_key"),
+            Err(KeyringError::ParseError(msg)) if msg == "secret 'api_key' is not valid UTF-16LE"
+        ));
+    }
+}
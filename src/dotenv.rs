@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Parsing of `.env`-style `KEY=VALUE` secrets.
+
+use crate::error::{KeyringError, Result};
+
+/// Parse a `.env`-style blob into key/value pairs, in order.
+///
+/// Blank lines and lines starting with `#` are skipped, an optional `export `
+/// prefix is accepted, and values may be wrapped in single or double quotes.
+/// Double-quoted values support the `\n`, `\"` and `\\` escapes; unquoted
+/// values end at an inline ` #` comment.
+pub fn parse(input: &str) -> Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| parse_error(line_number, "expected KEY=VALUE"))?;
+
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(parse_error(line_number, "invalid key"));
+        }
+
+        let value = parse_value(value.trim()).map_err(|msg| parse_error(line_number, msg))?;
+        pairs.push((key.to_string(), value));
+    }
+    Ok(pairs)
+}
+
+fn parse_value(value: &str) -> std::result::Result<String, &'static str> {
+    if let Some(rest) = value.strip_prefix('"') {
+        let mut parsed = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    return match chars.as_str().trim_start() {
+                        "" => Ok(parsed),
+                        rest if rest.starts_with('#') => Ok(parsed),
+                        _ => Err("unexpected characters after closing quote"),
+                    };
+                }
+                '\\' => match chars.next() {
+                    Some('n') => parsed.push('\n'),
+                    Some(c @ ('"' | '\\')) => parsed.push(c),
+                    Some(c) => {
+                        parsed.push('\\');
+                        parsed.push(c);
+                    }
+                    None => break,
+                },
+                c => parsed.push(c),
+            }
+        }
+        Err("unterminated double quote")
+    } else if let Some(rest) = value.strip_prefix('\'') {
+        let (parsed, trailing) = rest.split_once('\'').ok_or("unterminated single quote")?;
+        match trailing.trim_start() {
+            "" => Ok(parsed.to_string()),
+            rest if rest.starts_with('#') => Ok(parsed.to_string()),
+            _ => Err("unexpected characters after closing quote"),
+        }
+    } else {
+        let value = match value.find(" #") {
+            Some(comment) => &value[..comment],
+            None => value,
+        };
+        Ok(value.trim_end().to_string())
+    }
+}
+
+fn parse_error(line: usize, msg: &str) -> KeyringError {
+    KeyringError::ParseError(format!("dotenv line {}: {}", line, msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quoted_and_unquoted_values() {
+        let pairs = parse(
+            "\n# database\nexport HOST=localhost # inline\nUSER='admin # not a comment'\nPASS=\"p\\\"w\\nd\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("HOST".to_string(), "localhost".to_string()),
+                ("USER".to_string(), "admin # not a comment".to_string()),
+                ("PASS".to_string(), "p\"w\nd".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_error_names_line() {
+        let err = parse("A=1\n# comment\nnot a pair\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "secret parse error: dotenv line 3: expected KEY=VALUE"
+        );
+
+        let err = parse("A=\"unterminated\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "secret parse error: dotenv line 1: unterminated double quote"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Decoding of secrets stored in a text encoding.
+
+use crate::error::{KeyringError, Result};
+use crate::keyring_config::Encoding;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+
+/// Decode a secret stored with `encoding`.
+///
+/// Surrounding whitespace is ignored for every encoding but `Raw`.
+pub fn decode(secret: Vec<u8>, encoding: Encoding, name: &str) -> Result<Vec<u8>> {
+    let encoding = match encoding {
+        Encoding::Auto => detect(secret.trim_ascii()),
+        encoding => encoding,
+    };
+    let text = secret.trim_ascii();
+    let decoded = match encoding {
+        Encoding::Raw | Encoding::Auto => return Ok(secret),
+        Encoding::Hex => hex::decode(text).map_err(|e| e.to_string()),
+        Encoding::Base64 => STANDARD.decode(text).map_err(|e| e.to_string()),
+        Encoding::Base64Url => match text.last() {
+            Some(b'=') => URL_SAFE.decode(text),
+            _ => URL_SAFE_NO_PAD.decode(text),
+        }
+        .map_err(|e| e.to_string()),
+    };
+    decoded.map_err(|e| {
+        KeyringError::ParseError(format!(
+            "secret '{}' is not valid {:?}: {}",
+            name, encoding, e
+        ))
+    })
+}
+
+/// Guess the encoding of a secret from its alphabet.
+///
+/// An even number of hex digits is taken as hex. Otherwise text made of the
+/// standard base64 alphabet and padded to a multiple of four is base64, and
+/// text using `-` or `_` with the URL-safe alphabet is base64url. Anything
+/// else is raw.
+fn detect(text: &[u8]) -> Encoding {
+    let is_base64 = |c: &u8| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'/');
+    let is_base64_url = |c: &u8| c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_');
+    let unpadded = text
+        .strip_suffix(b"==")
+        .or_else(|| text.strip_suffix(b"="))
+        .unwrap_or(text);
+
+    if text.is_empty() {
+        Encoding::Raw
+    } else if text.len().is_multiple_of(2) && text.iter().all(u8::is_ascii_hexdigit) {
+        Encoding::Hex
+    } else if text.len().is_multiple_of(4) && unpadded.iter().all(is_base64) {
+        Encoding::Base64
+    } else if unpadded.iter().any(|c| matches!(c, b'-' | b'_'))
+        && unpadded.iter().all(is_base64_url)
+    {
+        Encoding::Base64Url
+    } else {
+        Encoding::Raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_each_encoding() {
+        let decode = |secret: &str, encoding| decode(secret.into(), encoding, "key").unwrap();
+        assert_eq!(decode("s3cret", Encoding::Raw), b"s3cret");
+        assert_eq!(decode("73336372 6574", Encoding::Raw), b"73336372 6574");
+        assert_eq!(decode("733363726574\n", Encoding::Hex), b"s3cret");
+        assert_eq!(decode("czNjcmV0", Encoding::Base64), b"s3cret");
+        assert_eq!(decode("+/8=", Encoding::Base64), [0xfb, 0xff]);
+        assert_eq!(decode("-_8", Encoding::Base64Url), [0xfb, 0xff]);
+        assert_eq!(decode("-_8=", Encoding::Base64Url), [0xfb, 0xff]);
+    }
+
+    #[test]
+    fn test_auto_detection() {
+        assert_eq!(detect(b"733363726574"), Encoding::Hex);
+        assert_eq!(detect(b"czNjcmV0"), Encoding::Base64);
+        assert_eq!(detect(b"+/8="), Encoding::Base64);
+        assert_eq!(detect(b"-_8"), Encoding::Base64Url);
+        assert_eq!(detect(b"hunter2!"), Encoding::Raw);
+        assert_eq!(
+            decode(b"czNjcmV0".to_vec(), Encoding::Auto, "key").unwrap(),
+            b"s3cret"
+        );
+    }
+
+    #[test]
+    fn test_decode_error_names_secret() {
+        assert!(matches!(
+            decode(b"xyz".to_vec(), Encoding::Hex, "api_key"),
+            Err(KeyringError::ParseError(msg)) if msg.starts_with("secret 'api_key' is not valid Hex")
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use std::time::Duration;
 use thiserror::Error;
 
 /// Result type for keyring operations.
@@ -17,9 +18,43 @@ pub enum KeyringError {
     #[error("keyring service unavailable: {0}")]
     ServiceUnavailable(String),
 
-    #[error("permission denied")]
-    PermissionDenied,
+    #[error("permission denied: {0}")]
+    PermissionDenied(String),
 
     #[error("backend error: {0}")]
     BackendError(String),
+
+    #[error("secret parse error: {0}")]
+    ParseError(String),
+
+    #[error("unsupported operation: {0}")]
+    Unsupported(String),
+
+    #[error("keyring skipped after repeated failures: {0}")]
+    CircuitOpen(String),
+
+    #[error("keyring did not respond within {0:?}")]
+    Timeout(Duration),
+}
+
+impl KeyringError {
+    /// Prefix the error's message with `context`.
+    ///
+    /// [`KeyringError::NotFound`] holds the missing name and
+    /// [`KeyringError::Timeout`] the time waited; both are returned
+    /// unchanged.
+    pub fn context(self, context: &str) -> Self {
+        let wrap = |msg: String| format!("{}: {}", context, msg);
+        match self {
+            KeyringError::NotFound(name) => KeyringError::NotFound(name),
+            KeyringError::ConfigError(msg) => KeyringError::ConfigError(wrap(msg)),
+            KeyringError::ServiceUnavailable(msg) => KeyringError::ServiceUnavailable(wrap(msg)),
+            KeyringError::PermissionDenied(msg) => KeyringError::PermissionDenied(wrap(msg)),
+            KeyringError::BackendError(msg) => KeyringError::BackendError(wrap(msg)),
+            KeyringError::ParseError(msg) => KeyringError::ParseError(wrap(msg)),
+            KeyringError::Unsupported(msg) => KeyringError::Unsupported(wrap(msg)),
+            KeyringError::CircuitOpen(msg) => KeyringError::CircuitOpen(wrap(msg)),
+            KeyringError::Timeout(waited) => KeyringError::Timeout(waited),
+        }
+    }
 }
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Ordered sources for a secret, see
+//! [`KeyringProvider::fallback_chain`](crate::KeyringProvider::fallback_chain).
+
+use crate::error::KeyringError;
+use std::path::PathBuf;
+
+/// One place a secret may come from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FallbackSource {
+    /// The configured keyrings, searched as without a chain
+    Keyring,
+    /// An environment variable
+    Env(String),
+    /// The contents of a file
+    File(PathBuf),
+    /// A fixed value
+    Literal(String),
+}
+
+impl FallbackSource {
+    /// Read a source other than [`FallbackSource::Keyring`], `None` when it
+    /// holds no secret.
+    pub(crate) fn read(&self) -> Result<Option<Vec<u8>>, KeyringError> {
+        match self {
+            FallbackSource::Keyring => Ok(None),
+            FallbackSource::Env(name) => match std::env::var(name) {
+                Ok(value) => Ok(Some(value.into_bytes())),
+                Err(std::env::VarError::NotPresent) => Ok(None),
+                Err(std::env::VarError::NotUnicode(_)) => Err(KeyringError::ParseError(format!(
+                    "environment variable '{}' is not valid UTF-8",
+                    name
+                ))),
+            },
+            FallbackSource::File(path) => match std::fs::read(path) {
+                Ok(value) => Ok(Some(value)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(KeyringError::BackendError(format!(
+                    "reading '{}': {}",
+                    path.display(),
+                    e
+                ))),
+            },
+            FallbackSource::Literal(value) => Ok(Some(value.clone().into_bytes())),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Secret presence summaries for readiness probes.
+
+use crate::KeyringProvider;
+use serde_json::{json, Value};
+
+/// Check that the required secret of every provider is present.
+///
+/// Returns `{"ok": bool, "missing": [...]}`, listing by credential name each
+/// secret that is absent or could not be checked. Secrets of providers whose
+/// config is `optional` are not required. No secret values are included.
+///
+/// ```rust,no_run
+/// # use figment_keyring::{KeyringProvider, health_check};
+/// let api_key = KeyringProvider::new("myapp", "api_key");
+/// let db_password = KeyringProvider::new("myapp", "db_password");
+/// let summary = health_check(&[&api_key, &db_password]);
+/// println!("{}", summary);
+/// ```
+pub fn health_check(providers: &[&KeyringProvider]) -> Value {
+    let mut missing = Vec::new();
+    for provider in providers {
+        let name = &provider.credential_name;
+        let optional = match provider.extract_config() {
+            Ok(config) => config.optional,
+            Err(e) => {
+                log::warn!("health check of '{}' failed: {}", name, e);
+                missing.push(name.clone());
+                continue;
+            }
+        };
+        match provider.exists() {
+            Ok(true) => {}
+            Ok(false) if optional => {}
+            Ok(false) => missing.push(name.clone()),
+            Err(e) => {
+                log::warn!("health check of '{}' failed: {}", name, e);
+                missing.push(name.clone());
+            }
+        }
+    }
+    json!({ "ok": missing.is_empty(), "missing": missing })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyringConfig;
+    use figment2::{providers::Serialized, Figment};
+    use keyring_core::api::CredentialStoreApi;
+    use keyring_core::mock;
+    use std::sync::Arc;
+
+    fn provider(store: &Arc<mock::Store>, name: &str, optional: bool) -> KeyringProvider {
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            optional,
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, name)
+            .with_entry_factory(crate::tests::mock_factory(store.clone()))
+    }
+
+    #[test]
+    fn test_health_check_reports_missing() {
+        let store = mock::Store::new().unwrap();
+        store
+            .build("test-app", "api_key", None)
+            .unwrap()
+            .set_password("s3cret")
+            .unwrap();
+
+        let present = provider(&store, "api_key", false);
+        let missing = provider(&store, "db_password", false);
+        let optional = provider(&store, "metrics_token", true);
+
+        assert_eq!(
+            health_check(&[&present, &missing, &optional]),
+            json!({ "ok": false, "missing": ["db_password"] })
+        );
+        assert_eq!(
+            health_check(&[&present, &optional]),
+            json!({ "ok": true, "missing": [] })
+        );
+    }
+}
@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Integrity checks for fetched secrets.
+
+use crate::error::{KeyringError, Result};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// SHA-256 digest of `secret`.
+pub fn sha256(secret: &[u8]) -> [u8; 32] {
+    Sha256::digest(secret).into()
+}
+
+/// Verify that `secret` hashes to the hex-encoded SHA-256 digest `expected`.
+///
+/// The digests are compared in constant time.
+pub fn verify_sha256(secret: &[u8], expected: &str) -> Result<()> {
+    let expected = hex::decode(expected.trim()).map_err(|e| {
+        KeyringError::ConfigError(format!("expected_sha256 is not valid hex: {}", e))
+    })?;
+    let actual = Sha256::digest(secret);
+    if bool::from(actual.as_slice().ct_eq(&expected)) {
+        Ok(())
+    } else {
+        Err(KeyringError::BackendError(
+            "secret integrity check failed".into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SHA-256 of "s3cret"
+    const S3CRET_SHA256: &str = "1ec1c26b50d5d3c58d9583181af8076655fe00756bf7285940ba3670f99fcba0";
+
+    #[test]
+    fn test_verify_sha256_match() {
+        assert!(verify_sha256(b"s3cret", S3CRET_SHA256).is_ok());
+        assert!(verify_sha256(b"s3cret", &S3CRET_SHA256.to_uppercase()).is_ok());
+    }
+
+    #[test]
+    fn test_verify_sha256_mismatch() {
+        let err = verify_sha256(b"tampered", S3CRET_SHA256).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "backend error: secret integrity check failed"
+        );
+    }
+
+    #[test]
+    fn test_verify_sha256_invalid_hex() {
+        assert!(matches!(
+            verify_sha256(b"s3cret", "not-hex"),
+            Err(KeyringError::ConfigError(_))
+        ));
+    }
+}
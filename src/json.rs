@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! JSON secrets merged into the provider output.
+
+use crate::error::{KeyringError, Result};
+use figment2::value::{Dict, Value};
+
+/// Parse a secret holding a JSON object into a dict.
+pub fn parse_object(secret: &[u8], name: &str) -> Result<Dict> {
+    serde_json::from_slice(secret)
+        .map_err(|e| KeyringError::ParseError(format!("'{}' is not a JSON object: {}", name, e)))
+}
+
+/// Deep-merge `from` into `into`.
+///
+/// Nested dicts are merged key by key; any other value in `from` replaces
+/// the value in `into`, so the last merged fragment wins on conflicts.
+pub fn deep_merge(into: &mut Dict, from: Dict) {
+    for (key, value) in from {
+        match (into.get_mut(&key), value) {
+            (Some(Value::Dict(_, existing)), Value::Dict(_, incoming)) => {
+                deep_merge(existing, incoming)
+            }
+            (_, value) => {
+                into.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Nest the top-level keys of `dict` on `separator`.
+///
+/// A key `"db.password"` split on `"."` becomes `{"db": {"password": ..}}`;
+/// keys sharing a prefix are deep-merged into the same dict.
+pub fn nest(dict: Dict, separator: &str) -> Dict {
+    let mut nested = Dict::new();
+    for (key, value) in dict {
+        let mut parts = key.rsplit(separator);
+        let mut value = value;
+        let mut leaf = parts.next().unwrap_or_default().to_string();
+        for part in parts {
+            value = Value::from(Dict::from([(leaf, value)]));
+            leaf = part.to_string();
+        }
+        deep_merge(&mut nested, Dict::from([(leaf, value)]));
+    }
+    nested
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deep_merge_last_wins() {
+        let mut merged = parse_object(br#"{"db": {"host": "a", "port": 1}, "x": 1}"#, "a").unwrap();
+        deep_merge(
+            &mut merged,
+            parse_object(br#"{"db": {"host": "b"}, "y": [2]}"#, "b").unwrap(),
+        );
+
+        let db = merged["db"].as_dict().unwrap();
+        assert_eq!(db["host"].as_str(), Some("b"));
+        assert_eq!(db["port"].to_u128(), Some(1));
+        assert_eq!(merged["x"].to_u128(), Some(1));
+        assert!(merged["y"].as_array().is_some());
+    }
+
+    #[test]
+    fn test_nest_splits_and_merges() {
+        let dict = Dict::from([
+            ("db::user".to_string(), Value::from("admin")),
+            ("db::password".to_string(), Value::from("secret")),
+            ("token".to_string(), Value::from("t")),
+        ]);
+        let nested = nest(dict, "::");
+
+        let db = nested["db"].as_dict().unwrap();
+        assert_eq!(db["user"].as_str(), Some("admin"));
+        assert_eq!(db["password"].as_str(), Some("secret"));
+        assert_eq!(nested["token"].as_str(), Some("t"));
+    }
+
+    #[test]
+    fn test_parse_object_rejects_non_object() {
+        assert!(matches!(
+            parse_object(b"[1, 2]", "cfg.part1"),
+            Err(KeyringError::ParseError(msg)) if msg.starts_with("'cfg.part1' is not a JSON object")
+        ));
+    }
+}
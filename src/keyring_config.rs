@@ -1,9 +1,15 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use serde::{Deserialize, Serialize};
+use crate::error::{KeyringError, Result};
+use figment2::{error::Kind, providers::Serialized, value::Value, Figment};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 
 /// Identifies which keyring to use.
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+///
+/// The well-known names `user`, `system` and `memory` are matched without
+/// regard to case; any other name is a [`Keyring::Named`] keyring.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Keyring {
     /// Current user's keyring (default)
@@ -11,6 +17,8 @@ pub enum Keyring {
     User,
     /// System-wide keyring
     System,
+    /// Process-local in-memory keyring, for tests and ephemeral runs
+    Memory,
     /// Custom named keyring
     #[serde(untagged)]
     Named(String),
@@ -18,14 +26,33 @@ pub enum Keyring {
 
 impl From<&str> for Keyring {
     fn from(s: &str) -> Self {
-        match s {
+        match s.to_ascii_lowercase().as_str() {
             "user" => Keyring::User,
             "system" => Keyring::System,
-            name => Keyring::Named(name.into()),
+            "memory" => Keyring::Memory,
+            _ => Keyring::Named(s.into()),
         }
     }
 }
 
+impl Keyring {
+    /// The keyring's name as written in config.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Keyring::User => "user",
+            Keyring::System => "system",
+            Keyring::Memory => "memory",
+            Keyring::Named(name) => name,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Keyring {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|s| Keyring::from(s.as_str()))
+    }
+}
+
 /// Configuration for keyring behavior.
 /// Deserializable from any Figment source.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -41,62 +68,930 @@ pub struct KeyringConfig {
     /// Don't fail if secret is not found in any keyring
     #[serde(default)]
     pub optional: bool,
+
+    /// How to handle a secret that is not valid UTF-8
+    #[serde(default)]
+    pub on_invalid_utf8: InvalidUtf8,
+
+    /// Which credential to read when several match the same entry
+    #[serde(default)]
+    pub on_ambiguous: OnAmbiguous,
+
+    /// Hex-encoded SHA-256 digest the secret must match; only for a
+    /// provider reading a single credential
+    #[serde(default)]
+    pub expected_sha256: Option<String>,
+
+    /// Also try the platform's candidate targets when searching the user keyring
+    #[serde(default)]
+    pub platform_defaults: bool,
+
+    /// Move on to the next keyring when access to one is denied
+    #[serde(default)]
+    pub continue_on_denied: bool,
+
+    /// Reject writes and deletes through the provider
+    #[serde(default)]
+    pub read_only: bool,
+
+    /// Whether each keyring is read, written or both; unlisted keyrings
+    /// are both
+    #[serde(default)]
+    pub keyring_roles: HashMap<Keyring, KeyringRole>,
+
+    /// Ask the store for non-interactive access, failing instead of
+    /// prompting; the native stores don't support it and fail with
+    /// [`KeyringError::Unsupported`]
+    #[serde(default)]
+    pub no_prompt: bool,
+
+    /// Fail early with a clear error when the system keyring is used without
+    /// elevated privileges
+    #[serde(default)]
+    pub check_privileges: bool,
+
+    /// Abort on any error from the first keyring, even when `optional`
+    #[serde(default)]
+    pub primary_strict: bool,
+
+    /// Order in which equivalent keyrings are read
+    #[serde(default)]
+    pub read_strategy: ReadStrategy,
+
+    /// Read the secret from every keyring holding it and fail when their
+    /// values differ
+    #[serde(default)]
+    pub detect_drift: bool,
+
+    /// Propagate backend errors even when `optional`, which then only
+    /// tolerates missing secrets
+    #[serde(default)]
+    pub strict_errors: bool,
+
+    /// Credential store to use instead of the native one, by the name it was
+    /// registered under with [`backend::register_store`]
+    #[serde(default)]
+    pub store: Option<String>,
+
+    /// Manifest of credentials to load together, each emitted under its own
+    /// name, instead of the provider's single credential
+    #[serde(default)]
+    pub credentials: Vec<ManifestEntry>,
+
+    /// Secrets to load together, each read from the keyring and service its
+    /// spec names, instead of the provider's single credential
+    #[serde(default)]
+    pub secrets: Vec<EntrySpec>,
+
+    /// Case applied to credential names before they reach the backend
+    #[serde(default)]
+    pub name_case: NameCase,
+
+    /// Substrings of backend error messages that mark an error as transient,
+    /// so the lookup is retried once instead of aborting the search
+    #[serde(default)]
+    pub transient_error_patterns: Vec<String>,
+
+    /// Target of the system keyring, replacing the platform default
+    #[serde(default)]
+    pub system_target: Option<String>,
+
+    /// Target of the user keyring, which otherwise uses the store's default
+    #[serde(default)]
+    pub user_target: Option<String>,
+
+    /// Largest secret, in bytes, accepted from a keyring
+    #[serde(default)]
+    pub max_secret_bytes: Option<usize>,
+
+    /// Type to convert the secret to before it is emitted, instead of a
+    /// string
+    #[serde(default)]
+    pub coerce: Option<ValueKind>,
+}
+
+impl Default for KeyringConfig {
+    fn default() -> Self {
+        Self {
+            service: String::new(),
+            keyrings: default_keyrings(),
+            optional: false,
+            on_invalid_utf8: InvalidUtf8::default(),
+            on_ambiguous: OnAmbiguous::default(),
+            expected_sha256: None,
+            platform_defaults: false,
+            continue_on_denied: false,
+            read_only: false,
+            keyring_roles: HashMap::new(),
+            no_prompt: false,
+            check_privileges: false,
+            primary_strict: false,
+            strict_errors: false,
+            read_strategy: ReadStrategy::default(),
+            detect_drift: false,
+            store: None,
+            credentials: Vec::new(),
+            secrets: Vec::new(),
+            name_case: NameCase::default(),
+            transient_error_patterns: Vec::new(),
+            system_target: None,
+            user_target: None,
+            max_secret_bytes: None,
+            coerce: None,
+        }
+    }
+}
+
+impl From<KeyringConfig> for Figment {
+    /// A figment holding `config` as defaults.
+    fn from(config: KeyringConfig) -> Self {
+        Figment::from(Serialized::defaults(config))
+    }
+}
+
+impl KeyringConfig {
+    /// Extract a config from a figment.
+    ///
+    /// A missing `service` is reported as such rather than as a generic
+    /// deserialization error.
+    pub fn from_figment(figment: &Figment) -> Result<Self> {
+        figment.extract().map_err(|e| {
+            let missing_service = e
+                .clone()
+                .into_iter()
+                .any(|e| e.path.is_empty() && e.kind == Kind::MissingField("service".into()));
+            if missing_service {
+                KeyringError::ConfigError("missing required 'service'".into())
+            } else {
+                KeyringError::ConfigError(e.to_string())
+            }
+        })
+    }
+
+    /// Keyrings to search, in order.
+    ///
+    /// With `platform_defaults`, [`Keyring::User`] is followed by the
+    /// platform's candidate targets from
+    /// [`platform_user_targets`](backend::platform_user_targets). Keyrings
+    /// with the [`Write`](KeyringRole::Write) role are left out.
+    pub fn search_order(&self) -> Vec<Keyring> {
+        let mut order: Vec<Keyring> = Vec::new();
+        for keyring in &self.keyrings {
+            if !self.role(keyring).can_read() {
+                continue;
+            }
+            let candidates = match keyring {
+                Keyring::User if self.platform_defaults => backend::platform_user_targets(),
+                _ => &[],
+            };
+            let expanded = std::iter::once(keyring.clone())
+                .chain(candidates.iter().map(|target| Keyring::from(*target)));
+            for keyring in expanded {
+                if !order.contains(&keyring) && self.role(&keyring).can_read() {
+                    order.push(keyring);
+                }
+            }
+        }
+        order
+    }
+
+    /// The role of `keyring` from `keyring_roles`.
+    pub fn role(&self, keyring: &Keyring) -> KeyringRole {
+        self.keyring_roles.get(keyring).copied().unwrap_or_default()
+    }
+
+    /// Fail unless the provider may write to or delete from `keyring`, i.e.
+    /// `read_only` is unset and the keyring's role allows writes.
+    pub fn check_writable(&self, keyring: &Keyring) -> Result<()> {
+        if self.read_only {
+            return Err(KeyringError::PermissionDenied(
+                "keyring is read-only".into(),
+            ));
+        }
+        if !self.role(keyring).can_write() {
+            return Err(KeyringError::PermissionDenied(format!(
+                "{} keyring is read-only",
+                keyring.as_str()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether errors from `keyring` abort the search under `primary_strict`.
+    pub fn is_strict(&self, keyring: &Keyring) -> bool {
+        self.primary_strict && self.keyrings.first() == Some(keyring)
+    }
+
+    /// Whether a backend error from `keyring` is tolerated like a missing
+    /// secret: `optional` is set and neither `strict_errors` nor
+    /// `primary_strict` applies.
+    pub fn tolerates_errors(&self, keyring: &Keyring) -> bool {
+        self.optional && !self.strict_errors && !self.is_strict(keyring)
+    }
+
+    /// Reject a fetched `secret` longer than `max_secret_bytes`.
+    pub fn check_secret_len(&self, secret: Vec<u8>) -> Result<Vec<u8>> {
+        match self.max_secret_bytes {
+            Some(max) if secret.len() > max => Err(KeyringError::BackendError(
+                "secret exceeds max length".into(),
+            )),
+            _ => Ok(secret),
+        }
+    }
+
+    /// Whether `error` is a [`KeyringError::Timeout`] or matches one of the
+    /// `transient_error_patterns`.
+    pub fn is_transient(&self, error: &KeyringError) -> bool {
+        match error {
+            KeyringError::NotFound(_) => return false,
+            KeyringError::Timeout(_) => return true,
+            _ => {}
+        }
+        let message = error.to_string();
+        self.transient_error_patterns
+            .iter()
+            .any(|pattern| message.contains(pattern.as_str()))
+    }
 }
 
 fn default_keyrings() -> Vec<Keyring> {
     vec![Keyring::User]
 }
 
+/// Handling of secrets that are not valid UTF-8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum InvalidUtf8 {
+    /// Replace invalid sequences with U+FFFD
+    Lossy,
+    /// Fail with a descriptive error (default)
+    #[default]
+    Error,
+    /// Emit the raw bytes as an array
+    Bytes,
+}
+
+/// Order in which the keyrings are read, for secrets replicated across
+/// equivalent keyrings.
+///
+/// Whatever the strategy, every keyring is still tried before a secret is
+/// reported missing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadStrategy {
+    /// Always start with the first keyring (default)
+    #[default]
+    FirstWins,
+    /// Start each read with the keyring after the one the previous read
+    /// started with
+    RoundRobin,
+    /// Try the keyrings in a random order
+    Random,
+}
+
+/// Whether a keyring is read from, written to, or both.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyringRole {
+    /// Only read; stores, mirrors and deletes skip or reject it
+    Read,
+    /// Only written, e.g. a backup mirror; never searched for secrets
+    Write,
+    /// Read and written (default)
+    #[default]
+    ReadWrite,
+}
+
+impl KeyringRole {
+    /// Whether secrets are searched for in the keyring.
+    pub fn can_read(self) -> bool {
+        self != KeyringRole::Write
+    }
+
+    /// Whether secrets may be written to or deleted from the keyring.
+    pub fn can_write(self) -> bool {
+        self != KeyringRole::Read
+    }
+}
+
+/// Handling of entries matched by more than one credential in the store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OnAmbiguous {
+    /// Fail with a descriptive error (default)
+    #[default]
+    Error,
+    /// Read the first credential the store returns
+    First,
+    /// Read the credential with the greatest `modified` attribute, compared
+    /// as strings; credentials without one count as oldest
+    Newest,
+}
+
+/// Case of the credential name used for backend lookups.
+///
+/// The emitted config key always keeps the name as given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NameCase {
+    /// Use the name as given (default)
+    #[default]
+    Preserve,
+    /// Lowercase the name
+    Lower,
+    /// Uppercase the name
+    Upper,
+}
+
+impl NameCase {
+    /// Apply the case to `name`.
+    pub fn apply(self, name: &str) -> String {
+        match self {
+            NameCase::Preserve => name.to_string(),
+            NameCase::Lower => name.to_lowercase(),
+            NameCase::Upper => name.to_uppercase(),
+        }
+    }
+}
+
+/// A credential in the [`KeyringConfig::credentials`] manifest.
+///
+/// Written either as a bare name or as a table that overrides `optional`
+/// for that one credential:
+///
+/// ```toml
+/// credentials = ["api_key", { name = "metrics_token", optional = true }]
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(from = "ManifestEntryRepr")]
+pub struct ManifestEntry {
+    /// Credential name, also the emitted key
+    pub name: String,
+    /// Whether a missing secret is skipped, overriding the config's
+    /// `optional` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optional: Option<bool>,
+}
+
+impl ManifestEntry {
+    /// Whether a missing secret is skipped under `config`.
+    pub fn is_optional(&self, config: &KeyringConfig) -> bool {
+        self.optional.unwrap_or(config.optional)
+    }
+}
+
+impl From<&str> for ManifestEntry {
+    fn from(name: &str) -> Self {
+        name.to_string().into()
+    }
+}
+
+impl From<String> for ManifestEntry {
+    fn from(name: String) -> Self {
+        Self {
+            name,
+            optional: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ManifestEntryRepr {
+    Name(String),
+    Entry {
+        name: String,
+        #[serde(default)]
+        optional: Option<bool>,
+    },
+}
+
+impl From<ManifestEntryRepr> for ManifestEntry {
+    fn from(repr: ManifestEntryRepr) -> Self {
+        match repr {
+            ManifestEntryRepr::Name(name) => name.into(),
+            ManifestEntryRepr::Entry { name, optional } => Self { name, optional },
+        }
+    }
+}
+
+/// One explicitly located secret of a config's `secrets` list, see
+/// [`KeyringProvider::from_entries`](crate::KeyringProvider::from_entries).
+///
+/// ```toml
+/// [[secrets]]
+/// keyring = "system"
+/// service = "billing"
+/// credential = "stripe_key"
+/// config_key = "payments.api_key"
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EntrySpec {
+    /// Keyring holding the secret
+    pub keyring: Keyring,
+    /// Service the secret is stored under
+    pub service: String,
+    /// Credential name the secret is stored under
+    pub credential: String,
+    /// Key the secret is emitted under, the credential name by default
+    #[serde(default)]
+    pub config_key: Option<String>,
+    /// Skip the secret when it is missing
+    #[serde(default)]
+    pub optional: bool,
+}
+
+/// Type a secret is converted to, see [`KeyringConfig::coerce`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueKind {
+    /// Keep the secret as a string
+    String,
+    /// A signed or unsigned integer
+    Integer,
+    /// A floating point number
+    Float,
+    /// `true` or `false`, in any case
+    #[serde(alias = "boolean")]
+    Bool,
+}
+
+impl ValueKind {
+    /// Convert `secret`, named `name` in errors, to a value of this kind.
+    pub fn coerce(self, secret: &str, name: &str) -> Result<Value> {
+        let trimmed = secret.trim();
+        let value = match self {
+            ValueKind::String => Some(Value::from(secret)),
+            ValueKind::Integer => trimmed
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| trimmed.parse::<u64>().map(Value::from))
+                .ok(),
+            ValueKind::Float => trimmed.parse::<f64>().map(Value::from).ok(),
+            ValueKind::Bool => match trimmed.to_ascii_lowercase().as_str() {
+                "true" => Some(Value::from(true)),
+                "false" => Some(Value::from(false)),
+                _ => None,
+            },
+        };
+        value.ok_or_else(|| {
+            let kind = format!("{:?}", self).to_lowercase();
+            KeyringError::ParseError(format!("secret '{}' is not a valid {}", name, kind))
+        })
+    }
+}
+
+/// Text encoding a secret is stored in, see
+/// [`KeyringProvider::with_encoding`](crate::KeyringProvider::with_encoding).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    /// Use the stored bytes as they are (default)
+    #[default]
+    Raw,
+    /// Hexadecimal digits
+    Hex,
+    /// Standard base64 with padding
+    Base64,
+    /// URL-safe base64, padded or not
+    Base64Url,
+    /// Detect hex, base64 or base64url, falling back to raw
+    Auto,
+}
+
 pub mod backend {
     use crate::error::{KeyringError, Result};
-    use crate::keyring_config::Keyring;
-    use keyring_core::Entry;
+    use crate::keyring_config::{Keyring, KeyringConfig, OnAmbiguous};
+    use keyring_core::{mock, CredentialStore, Entry};
 
     use std::collections::HashMap;
-    use std::sync::Once;
+    use std::future::Future;
+    use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+
+    /// The default store entries are built from when no store is named, or
+    /// why the native store could not be installed.
+    static DEFAULT_STORE: OnceLock<std::result::Result<Arc<CredentialStore>, String>> =
+        OnceLock::new();
+
+    static STORES: LazyLock<Mutex<HashMap<String, Arc<CredentialStore>>>> =
+        LazyLock::new(|| Mutex::new(HashMap::new()));
+
+    /// Backs [`Keyring::Memory`]; lives as long as the process.
+    static MEMORY: LazyLock<Arc<CredentialStore>> =
+        LazyLock::new(|| mock::Store::new().expect("Failed to create in-memory keyring store"));
+
+    /// Factory that builds the keyring entry for a keyring, service and username.
+    ///
+    /// The last argument holds the extra modifiers requested by the config,
+    /// such as [`NO_PROMPT_MODIFIER`].
+    pub type EntryFactory =
+        Arc<dyn Fn(&Keyring, &str, &str, &HashMap<&str, &str>) -> Result<Entry> + Send + Sync>;
+
+    /// Lists the entries stored for a service in a keyring.
+    ///
+    /// The last argument holds the extra modifiers requested by the config,
+    /// as passed to [`EntryFactory`], so a `target` override is searched too.
+    pub type EntrySearch =
+        Arc<dyn Fn(&Keyring, &str, &HashMap<&str, &str>) -> Result<Vec<Entry>> + Send + Sync>;
 
-    static INIT: Once = Once::new();
+    /// Asynchronous source of secrets, for stores that are inherently async
+    /// (remote vaults, HTTP secret managers, ...).
+    ///
+    /// This is the async counterpart of [`EntryFactory`], used by
+    /// [`KeyringProvider::data_async`](crate::KeyringProvider::data_async).
+    /// Implementations may use `async fn get`. A missing secret should be
+    /// reported as [`KeyringError::NotFound`] so the search moves on to the
+    /// next keyring.
+    pub trait AsyncSecretBackend {
+        /// Fetch the secret for `username` under `service` from `keyring`.
+        fn get(
+            &self,
+            keyring: &Keyring,
+            service: &str,
+            username: &str,
+        ) -> impl Future<Output = Result<String>> + Send;
+    }
+
+    /// Whether the process runs with elevated privileges.
+    ///
+    /// On Unix this means an effective user id of root, on Windows an
+    /// elevated (administrator) process token. Other platforms fail with
+    /// [`KeyringError::Unsupported`] rather than passing the check unseen.
+    pub fn is_elevated() -> Result<bool> {
+        #[cfg(unix)]
+        {
+            // SAFETY: geteuid has no preconditions and cannot fail.
+            Ok(unsafe { libc::geteuid() == 0 })
+        }
+        #[cfg(windows)]
+        {
+            use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+            use windows_sys::Win32::Security::{
+                GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+            };
+            use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+            let mut token: HANDLE = std::ptr::null_mut();
+            let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+            let mut len = 0u32;
+            // SAFETY: the token is only used after OpenProcessToken succeeds
+            // and is closed before returning; GetTokenInformation writes at
+            // most the size passed for `elevation`.
+            unsafe {
+                if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+                    return Err(KeyringError::PermissionDenied(
+                        "cannot open the process token to check privileges".into(),
+                    ));
+                }
+                let queried = GetTokenInformation(
+                    token,
+                    TokenElevation,
+                    (&mut elevation as *mut TOKEN_ELEVATION).cast(),
+                    size_of::<TOKEN_ELEVATION>() as u32,
+                    &mut len,
+                );
+                CloseHandle(token);
+                if queried == 0 {
+                    return Err(KeyringError::PermissionDenied(
+                        "cannot query the process token elevation".into(),
+                    ));
+                }
+            }
+            Ok(elevation.TokenIsElevated != 0)
+        }
+        #[cfg(not(any(unix, windows)))]
+        {
+            Err(KeyringError::Unsupported(
+                "check_privileges is not supported on this platform".into(),
+            ))
+        }
+    }
+
+    /// Entry modifier requesting non-interactive access, set by
+    /// [`KeyringConfig::no_prompt`].
+    pub const NO_PROMPT_MODIFIER: &str = "no-prompt";
+
+    /// Entry modifier carrying a correlation id for audited access, set by
+    /// [`KeyringProvider::with_context`](crate::KeyringProvider::with_context).
+    pub const ACCESS_REASON_MODIFIER: &str = "access-reason";
+
+    /// Extra entry modifiers requested by a config for entries in
+    /// `keyring`.
+    ///
+    /// The config's `system_target` or `user_target` is passed as the
+    /// `target` modifier of the matching keyring.
+    pub fn entry_modifiers<'a>(
+        config: &'a KeyringConfig,
+        keyring: &Keyring,
+    ) -> HashMap<&'static str, &'a str> {
+        let mut modifiers = HashMap::new();
+        if config.no_prompt {
+            modifiers.insert(NO_PROMPT_MODIFIER, "true");
+        }
+        let target = match keyring {
+            Keyring::System => config.system_target.as_deref(),
+            Keyring::User => config.user_target.as_deref(),
+            _ => None,
+        };
+        if let Some(target) = target {
+            modifiers.insert("target", target);
+        }
+        modifiers
+    }
 
     /// Get a secret from specified keyring.
     pub fn get_secret(keyring: &Keyring, service: &str, username: &str) -> Result<String> {
-        ensure_native_store_initialized();
         let entry = create_entry(keyring, service, username)?;
-        let password = entry
-            .get_password()
-            .map_err(|e| KeyringError::BackendError(e.to_string()))?;
+        read_secret(&entry)
+    }
+
+    /// Read the password stored in an entry.
+    pub fn read_secret(entry: &Entry) -> Result<String> {
+        let password = entry.get_password().map_err(|e| read_error(entry, e))?;
         Ok(password)
     }
 
-    fn ensure_native_store_initialized() {
-        INIT.call_once(|| {
-            keyring::use_native_store(false).expect("Failed to initialize native keyring store");
-        });
+    /// Read the raw secret bytes stored in an entry.
+    pub fn read_secret_bytes(entry: &Entry) -> Result<Vec<u8>> {
+        entry.get_secret().map_err(|e| read_error(entry, e))
+    }
+
+    /// Read the raw secret bytes stored in an entry, choosing among several
+    /// matching credentials according to `policy`.
+    pub fn read_unambiguous(entry: &Entry, policy: OnAmbiguous) -> Result<Vec<u8>> {
+        let entries = match entry.get_secret() {
+            Err(keyring_core::Error::Ambiguous(entries)) => entries,
+            result => return result.map_err(|e| read_error(entry, e)),
+        };
+        let chosen = match policy {
+            OnAmbiguous::Error => None,
+            OnAmbiguous::First => entries.first(),
+            OnAmbiguous::Newest => {
+                let mut newest: Option<(&Entry, Option<String>)> = None;
+                for candidate in &entries {
+                    let modified = read_attributes(candidate)
+                        .ok()
+                        .and_then(|mut attributes| attributes.remove("modified"));
+                    if newest.as_ref().is_none_or(|(_, best)| modified > *best) {
+                        newest = Some((candidate, modified));
+                    }
+                }
+                newest.map(|(entry, _)| entry)
+            }
+        };
+        match chosen {
+            Some(chosen) => read_secret_bytes(chosen),
+            None => Err(KeyringError::BackendError(format!(
+                "entry is ambiguous: {} credentials match",
+                entries.len()
+            ))),
+        }
+    }
+
+    /// Write a secret into an entry, replacing any existing value.
+    pub fn write_secret(entry: &Entry, secret: &str) -> Result<()> {
+        entry
+            .set_password(secret)
+            .map_err(|e| KeyringError::BackendError(e.to_string()))
+    }
+
+    /// Attach attributes to the credential behind an entry.
+    ///
+    /// Stores that cannot hold attributes fail with
+    /// [`KeyringError::Unsupported`].
+    pub fn write_attributes(entry: &Entry, attributes: &HashMap<String, String>) -> Result<()> {
+        let attributes: HashMap<&str, &str> = attributes
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        entry.update_attributes(&attributes).map_err(|e| match e {
+            e @ keyring_core::Error::NotSupportedByStore(_) => {
+                KeyringError::Unsupported(e.to_string())
+            }
+            e => KeyringError::BackendError(e.to_string()),
+        })
+    }
+
+    /// Read the attributes of the credential behind an entry.
+    pub fn read_attributes(entry: &Entry) -> Result<HashMap<String, String>> {
+        entry.get_attributes().map_err(|e| read_error(entry, e))
+    }
+
+    /// Write raw secret bytes to an entry.
+    pub fn write_secret_bytes(entry: &Entry, secret: &[u8]) -> Result<()> {
+        entry
+            .set_secret(secret)
+            .map_err(|e| KeyringError::BackendError(e.to_string()))
+    }
+
+    /// Delete the secret stored in an entry.
+    pub fn delete_secret(entry: &Entry) -> Result<()> {
+        entry.delete_credential().map_err(|e| read_error(entry, e))
+    }
+
+    fn read_error(entry: &Entry, error: keyring_core::Error) -> KeyringError {
+        match error {
+            keyring_core::Error::NoEntry => KeyringError::NotFound(
+                entry
+                    .get_specifiers()
+                    .map(|(_, username)| username)
+                    .unwrap_or_default(),
+            ),
+            e @ keyring_core::Error::NoStorageAccess(_) => {
+                KeyringError::PermissionDenied(e.to_string())
+            }
+            e => KeyringError::BackendError(e.to_string()),
+        }
+    }
+
+    /// List the entries stored for a service in the native store.
+    pub fn search_entries(keyring: &Keyring, service: &str) -> Result<Vec<Entry>> {
+        search_entries_in(None, keyring, service, &HashMap::new())
+    }
+
+    /// List the entries stored for a service in the credential store named
+    /// `store`, or the native store when `store` is `None`.
+    ///
+    /// A `target` modifier, as set by [`entry_modifiers`], is searched in
+    /// place of the keyring's platform default, matching
+    /// [`create_entry_in`].
+    pub fn search_entries_in(
+        store: Option<&str>,
+        keyring: &Keyring,
+        service: &str,
+        modifiers: &HashMap<&str, &str>,
+    ) -> Result<Vec<Entry>> {
+        if *keyring == Keyring::Memory {
+            let spec = HashMap::from([("service", service)]);
+            let entries = MEMORY
+                .search(&spec)
+                .map_err(|e| KeyringError::BackendError(e.to_string()))?;
+            // The in-memory store matches substrings, so keep exact matches.
+            return Ok(entries
+                .into_iter()
+                .filter(|entry| entry.get_specifiers().is_some_and(|(s, _)| s == service))
+                .collect());
+        }
+
+        let store = ensure_store_initialized(store)?;
+        let target = match keyring {
+            Keyring::Memory => None,
+            Keyring::User => modifiers.get("target").map(|target| target.to_string()),
+            Keyring::System => Some(
+                modifiers
+                    .get("target")
+                    .map_or_else(default_target, |target| target.to_string()),
+            ),
+            Keyring::Named(name) => Some(name.clone()),
+        };
+        let mut spec = HashMap::new();
+        spec.insert("service", service);
+        if let Some(target) = &target {
+            spec.insert("target", target.as_str());
+        }
+        store.search(&spec).map_err(|e| match e {
+            e @ keyring_core::Error::NotSupportedByStore(_) => {
+                KeyringError::Unsupported(e.to_string())
+            }
+            e => KeyringError::BackendError(e.to_string()),
+        })
+    }
+
+    /// Register a credential store under `name`, so configs can select it
+    /// with [`KeyringConfig::store`].
+    pub fn register_store(name: &str, store: Arc<CredentialStore>) {
+        STORES
+            .lock()
+            .expect("credential store registry poisoned")
+            .insert(name.into(), store);
+    }
+
+    /// The store selected by `store`, ready to build entries.
+    ///
+    /// Without a name the native store is installed as keyring-core's
+    /// default store, once, and returned. A default store the application
+    /// installed first is kept as is. A name resolves to the store
+    /// registered under it; the default store is never touched for it.
+    ///
+    /// Initialization holds the store registry lock, so providers
+    /// initializing concurrently don't race. If the default store is
+    /// replaced after initialization, entries would no longer come from the
+    /// store the application sees, so that is reported as
+    /// [`KeyringError::ServiceUnavailable`] instead.
+    fn ensure_store_initialized(store: Option<&str>) -> Result<Arc<CredentialStore>> {
+        let Some(name) = store else {
+            let _stores = STORES.lock().expect("credential store registry poisoned");
+            if keyring_core::get_default_store().is_none() {
+                platform::supported()?;
+            }
+            let initialized = DEFAULT_STORE
+                .get_or_init(|| {
+                    if keyring_core::get_default_store().is_none() {
+                        platform::use_native_store()?;
+                    }
+                    keyring_core::get_default_store()
+                        .ok_or_else(|| "no default store was installed".to_string())
+                })
+                .as_ref()
+                .map_err(|e| {
+                    KeyringError::ServiceUnavailable(format!(
+                        "native credential store could not be initialized: {}",
+                        e
+                    ))
+                })?;
+            return match keyring_core::get_default_store() {
+                Some(current) if Arc::ptr_eq(&current, initialized) => Ok(current),
+                _ => Err(KeyringError::ServiceUnavailable(
+                    "the default credential store was replaced after initialization".into(),
+                )),
+            };
+        };
+        STORES
+            .lock()
+            .expect("credential store registry poisoned")
+            .get(name)
+            .cloned()
+            .ok_or_else(|| {
+                KeyringError::ConfigError(format!("unknown credential store '{}'", name))
+            })
+    }
+
+    /// Create a keyring entry for specified keyring type in the native store.
+    pub fn create_entry(keyring: &Keyring, service: &str, username: &str) -> Result<Entry> {
+        create_entry_with_modifiers(keyring, service, username, &HashMap::new())
     }
 
-    /// Create a keyring entry for specified keyring type.
-    fn create_entry(
+    /// Create a native store entry, passing along extra `modifiers`.
+    ///
+    /// No native store documents a non-interactive mode, so
+    /// [`NO_PROMPT_MODIFIER`] fails with [`KeyringError::Unsupported`] rather
+    /// than being passed on as an unknown attribute.
+    pub fn create_entry_with_modifiers(
         keyring: &Keyring,
         service: &str,
         username: &str,
-    ) -> std::result::Result<Entry, KeyringError> {
-        let entry = match keyring {
-            Keyring::User => Entry::new(service, username)
-                .map_err(|e| KeyringError::BackendError(e.to_string()))?,
+        modifiers: &HashMap<&str, &str>,
+    ) -> Result<Entry> {
+        create_entry_in(None, keyring, service, username, modifiers)
+    }
+
+    /// Create an entry in the credential store named `store`, or the native
+    /// store when `store` is `None`.
+    ///
+    /// A named store receives all `modifiers`; the native store rejects
+    /// [`NO_PROMPT_MODIFIER`], as in [`create_entry_with_modifiers`]. A
+    /// `target` modifier takes the place of the system keyring's platform default.
+    /// [`Keyring::Memory`] entries always come from the process-local
+    /// in-memory store, which takes no modifiers.
+    pub fn create_entry_in(
+        store: Option<&str>,
+        keyring: &Keyring,
+        service: &str,
+        username: &str,
+        modifiers: &HashMap<&str, &str>,
+    ) -> Result<Entry> {
+        if *keyring == Keyring::Memory {
+            return MEMORY
+                .build(service, username, None)
+                .map_err(|e| KeyringError::BackendError(e.to_string()));
+        }
+
+        if store.is_none() && modifiers.contains_key(NO_PROMPT_MODIFIER) {
+            return Err(KeyringError::Unsupported(
+                "no_prompt is not supported by the native credential store".into(),
+            ));
+        }
+        let store = ensure_store_initialized(store)?;
+        let default_target = default_target();
+        let mut passed = modifiers.clone();
+        match keyring {
+            Keyring::User | Keyring::Memory => {}
             Keyring::System => {
-                let target = default_target();
-                let mut modifiers = HashMap::new();
-                modifiers.insert("target", target.as_str());
-                Entry::new_with_modifiers(service, username, &modifiers)
-                    .map_err(|e| KeyringError::BackendError(e.to_string()))?
+                passed.entry("target").or_insert(default_target.as_str());
             }
             Keyring::Named(name) => {
-                let mut modifiers = HashMap::new();
-                modifiers.insert("target", name.as_str());
-                Entry::new_with_modifiers(service, username, &modifiers)
-                    .map_err(|e| KeyringError::BackendError(e.to_string()))?
+                passed.insert("target", name.as_str());
             }
-        };
-        Ok(entry)
+        }
+
+        store
+            .build(service, username, (!passed.is_empty()).then_some(&passed))
+            .map_err(|e| KeyringError::BackendError(e.to_string()))
+    }
+
+    /// Candidate targets for the user keyring on this platform, in the order
+    /// they are tried.
+    ///
+    /// On Linux these are the GNOME Keyring and KWallet default collections.
+    pub fn platform_user_targets() -> &'static [&'static str] {
+        #[cfg(target_os = "linux")]
+        {
+            &["login", "kdewallet"]
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            &[]
+        }
     }
 
     fn default_target() -> String {
@@ -112,5 +1007,219 @@ pub mod backend {
         {
             "default".to_string()
         }
+        #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+        {
+            "default".to_string()
+        }
+    }
+
+    /// The native stores of the `keyring` crate.
+    #[cfg(not(target_family = "wasm"))]
+    mod native {
+        use crate::error::Result;
+
+        /// Check that a native store can exist on this platform.
+        pub fn supported() -> Result<()> {
+            Ok(())
+        }
+
+        pub fn use_native_store() -> std::result::Result<(), String> {
+            keyring::use_native_store(false).map_err(|e| e.to_string())
+        }
+    }
+
+    /// Stand-in for platforms without a system keyring, such as `wasm32`.
+    ///
+    /// Stores the application installs itself and the in-memory keyring
+    /// still work; anything needing a native store fails with
+    /// [`KeyringError::ServiceUnavailable`].
+    #[cfg(any(target_family = "wasm", test))]
+    mod unsupported {
+        use crate::error::{KeyringError, Result};
+
+        const UNSUPPORTED: &str = "keyring unsupported on this platform";
+
+        pub fn supported() -> Result<()> {
+            Err(KeyringError::ServiceUnavailable(UNSUPPORTED.into()))
+        }
+
+        pub fn use_native_store() -> std::result::Result<(), String> {
+            Err(UNSUPPORTED.into())
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    use native as platform;
+    #[cfg(target_family = "wasm")]
+    use unsupported as platform;
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_unsupported_platform_stub() {
+            assert!(matches!(
+                unsupported::supported(),
+                Err(KeyringError::ServiceUnavailable(msg))
+                    if msg == "keyring unsupported on this platform"
+            ));
+            assert!(unsupported::use_native_store().is_err());
+            assert!(platform::supported().is_ok());
+        }
+
+        #[test]
+        fn test_no_prompt_rejected_by_native_store() {
+            let modifiers = HashMap::from([(NO_PROMPT_MODIFIER, "true")]);
+            assert!(matches!(
+                create_entry_with_modifiers(&Keyring::User, "myapp", "api_key", &modifiers),
+                Err(KeyringError::Unsupported(_))
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment2::value::Value;
+
+    #[test]
+    fn test_figment_round_trip() {
+        let config = KeyringConfig {
+            service: "myapp".into(),
+            keyrings: vec![Keyring::System, Keyring::from("team")],
+            optional: true,
+            on_invalid_utf8: InvalidUtf8::Bytes,
+            store: Some("sqlite".into()),
+            ..Default::default()
+        };
+
+        let figment: Figment = config.clone().into();
+        let restored = KeyringConfig::from_figment(&figment).unwrap();
+        let figment: Figment = restored.clone().into();
+        let twice = KeyringConfig::from_figment(&figment).unwrap();
+
+        let expected = Value::serialize(&config).unwrap();
+        assert_eq!(Value::serialize(&restored).unwrap(), expected);
+        assert_eq!(Value::serialize(&twice).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_manifest_entry_forms() {
+        let figment = Figment::from(Serialized::defaults(serde_json::json!({
+            "service": "myapp",
+            "credentials": ["api_key", { "name": "metrics_token", "optional": true }],
+        })));
+        let config = KeyringConfig::from_figment(&figment).unwrap();
+
+        assert_eq!(
+            config.credentials,
+            vec![
+                ManifestEntry::from("api_key"),
+                ManifestEntry {
+                    name: "metrics_token".into(),
+                    optional: Some(true),
+                },
+            ]
+        );
+        assert!(!config.credentials[0].is_optional(&config));
+        assert!(config.credentials[1].is_optional(&config));
+    }
+
+    #[test]
+    fn test_keyring_deserialize_case_insensitive() {
+        let keyrings: Vec<Keyring> =
+            serde_json::from_str(r#"["User", "SYSTEM", "Memory", "Team-Secrets"]"#).unwrap();
+        assert_eq!(
+            keyrings,
+            vec![
+                Keyring::User,
+                Keyring::System,
+                Keyring::Memory,
+                Keyring::Named("Team-Secrets".into()),
+            ]
+        );
+
+        let figment = Figment::from(Serialized::defaults(serde_json::json!({
+            "service": "myapp",
+            "keyrings": ["User", "team-secrets"],
+        })));
+        let config = KeyringConfig::from_figment(&figment).unwrap();
+        assert_eq!(
+            config.keyrings,
+            vec![Keyring::User, Keyring::Named("team-secrets".into())]
+        );
+    }
+
+    #[test]
+    fn test_entry_modifiers_target_overrides() {
+        let config = KeyringConfig {
+            service: "myapp".into(),
+            user_target: Some("login".into()),
+            system_target: Some("custom".into()),
+            ..Default::default()
+        };
+
+        let modifiers = |keyring| {
+            backend::entry_modifiers(&config, &keyring)
+                .get("target")
+                .copied()
+        };
+        assert_eq!(modifiers(Keyring::User), Some("login"));
+        assert_eq!(modifiers(Keyring::System), Some("custom"));
+        assert_eq!(modifiers(Keyring::from("team")), None);
+    }
+
+    #[test]
+    fn test_check_writable_names_keyring() {
+        let config = KeyringConfig {
+            service: "myapp".into(),
+            keyring_roles: HashMap::from([(Keyring::from("backup"), KeyringRole::Read)]),
+            ..Default::default()
+        };
+        assert!(config.check_writable(&Keyring::User).is_ok());
+        assert!(matches!(
+            config.check_writable(&Keyring::from("backup")),
+            Err(KeyringError::PermissionDenied(msg)) if msg == "backup keyring is read-only"
+        ));
+    }
+
+    #[test]
+    fn test_from_figment_missing_service() {
+        assert!(matches!(
+            KeyringConfig::from_figment(&Figment::new()),
+            Err(KeyringError::ConfigError(msg)) if msg == "missing required 'service'"
+        ));
+    }
+
+    #[test]
+    fn test_search_order_without_platform_defaults() {
+        let config = KeyringConfig {
+            service: "test-app".into(),
+            keyrings: vec![Keyring::User, Keyring::System],
+            ..Default::default()
+        };
+        assert_eq!(config.search_order(), vec![Keyring::User, Keyring::System]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_search_order_linux_platform_defaults() {
+        let config = KeyringConfig {
+            service: "test-app".into(),
+            keyrings: vec![Keyring::User, Keyring::from("kdewallet"), Keyring::System],
+            platform_defaults: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.search_order(),
+            vec![
+                Keyring::User,
+                Keyring::Named("login".into()),
+                Keyring::Named("kdewallet".into()),
+                Keyring::System,
+            ]
+        );
     }
 }
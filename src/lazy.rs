@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Secrets fetched on first use, see
+//! [`KeyringProvider::lazy`](crate::KeyringProvider::lazy).
+
+use crate::error::Result;
+use crate::KeyringProvider;
+use std::sync::OnceLock;
+
+/// A secret that is looked up the first time it is asked for.
+///
+/// figment reads every provider as soon as it is merged, and its values
+/// cannot defer work, so a provider merged into a figment always fetches its
+/// secret. A `LazySecret` is kept outside the figment instead, typically
+/// next to the extracted config, and touches the keyring only when
+/// [`get`](Self::get) is called. Fetched secrets are kept for later calls;
+/// errors are not, so a failed lookup is tried again.
+pub struct LazySecret {
+    provider: KeyringProvider,
+    secret: OnceLock<Option<String>>,
+}
+
+impl LazySecret {
+    pub(crate) fn new(provider: KeyringProvider) -> Self {
+        Self {
+            provider,
+            secret: OnceLock::new(),
+        }
+    }
+
+    /// The secret, fetched with [`KeyringProvider::secret`] on first call.
+    pub fn get(&self) -> Result<Option<&str>> {
+        if let Some(secret) = self.secret.get() {
+            return Ok(secret.as_deref());
+        }
+        let secret = self.provider.secret()?;
+        Ok(self.secret.get_or_init(|| secret).as_deref())
+    }
+
+    /// Whether the secret has been fetched.
+    pub fn is_fetched(&self) -> bool {
+        self.secret.get().is_some()
+    }
+}
+
+impl std::fmt::Debug for LazySecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazySecret")
+            .field("fetched", &self.is_fetched())
+            .finish_non_exhaustive()
+    }
+}
@@ -57,19 +57,76 @@
 //! let provider = KeyringProvider::configured_by(config_figment, "api_key")
 //!     .focused("keyring");
 //! ```
+//!
+//! ## Provenance
+//!
+//! When the provider is merged, figment tags every value it emits with the
+//! provider's [`Metadata`], named `keyring` with a source marking it as
+//! sensitive. [`Figment::find_metadata`] and figment's error messages
+//! therefore attribute keyring-sourced values to the keyring:
+//!
+//! ```rust,no_run
+//! # use figment2::Figment;
+//! # use figment_keyring::KeyringProvider;
+//! let figment = Figment::new().merge(KeyringProvider::new("myapp", "api_key"));
+//! let metadata = figment.find_metadata("api_key");
+//! assert_eq!(metadata.map(|m| &*m.name), Some("keyring"));
+//! ```
+//!
+//! ## Value Types
+//!
+//! Secrets are emitted as string values, unless the config sets `coerce` or
+//! an option such as [`as_list`](KeyringProvider::as_list) reshapes them.
+//! [`Figment::extract`] never reinterprets a string, so a secret such as
+//! `0123` or `1e5` reaches a `String` field verbatim. Only
+//! [`Figment::extract_lossy`] converts strings that look like numbers or
+//! booleans, and only for fields of those types.
 
+#[cfg(feature = "encoding")]
+mod charset;
+mod dotenv;
+mod encoding;
 pub mod error;
+mod fallback;
+#[cfg(feature = "health")]
+mod health;
+mod integrity;
+mod json;
 pub mod keyring_config;
+mod lazy;
+pub mod naming;
+#[cfg(feature = "redact")]
+mod redact;
+mod value;
 
+#[cfg(feature = "encoding")]
+pub use charset::Charset;
 pub use error::KeyringError;
-pub use keyring_config::{Keyring, KeyringConfig};
+pub use fallback::FallbackSource;
+#[cfg(feature = "health")]
+pub use health::health_check;
+pub use keyring_config::backend::{AsyncSecretBackend, EntryFactory, EntrySearch};
+pub use keyring_config::{
+    Encoding, EntrySpec, InvalidUtf8, Keyring, KeyringConfig, KeyringRole, ManifestEntry, NameCase,
+    OnAmbiguous, ReadStrategy, ValueKind,
+};
+pub use lazy::LazySecret;
+pub use naming::NamingStrategy;
+#[cfg(feature = "redact")]
+pub use redact::{extract_redacted, SecretString};
 
 use figment2::{
+    error::{Actual, Kind},
     providers::Serialized,
     value::{Dict, Map, Value},
-    Error, Figment, Metadata, Profile, Provider,
+    Error, Figment, Metadata, Profile, Provider, Source,
 };
-use std::sync::Arc;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use value::KeyringValue;
 
 /// Provider that fetches secrets from system keyrings.
 ///
@@ -89,20 +146,358 @@ use std::sync::Arc;
 ///
 /// let provider = KeyringProvider::configured_by(config_figment, "api_key");
 /// ```
+///
+/// Figment implements [`Provider`] for references to providers, so a provider
+/// held in a struct can be merged by reference into several figments:
+///
+/// ```rust,no_run
+/// # use figment2::Figment;
+/// # use figment_keyring::KeyringProvider;
+/// let provider = KeyringProvider::new("myapp", "api_key");
+/// let first = Figment::new().merge(&provider);
+/// let second = Figment::new().merge(&provider);
+/// ```
+#[derive(Clone)]
 pub struct KeyringProvider {
     config_figment: Arc<Figment>,
     credential_name: String,
     config_key: Option<String>,
     profile: Option<Profile>,
+    entry_factory: Option<EntryFactory>,
+    entry_search: Option<EntrySearch>,
+    profile_delimiter: Option<char>,
+    profile_suffixes: Vec<String>,
+    snapshot_export: bool,
+    snapshot: Option<Arc<Dict>>,
+    dotenv: bool,
+    enabled_key: Option<String>,
+    credential_overrides: Arc<HashMap<Keyring, String>>,
+    config_mapper: Option<ConfigMapper>,
+    config_retry: Option<(u32, ConfigReloader)>,
+    error_mapper: Option<ErrorMapper>,
+    help: Option<String>,
+    #[cfg(feature = "generate")]
+    rng: Option<SharedRng>,
+    access_hook: Option<AccessHook>,
+    consume: bool,
+    strict_errors: bool,
+    naming: Option<Arc<dyn NamingStrategy>>,
+    swap_names: bool,
+    access_context: Option<String>,
+    source_key: bool,
+    credential_form: bool,
+    fallback_chain: Vec<FallbackSource>,
+    or_else: Option<Box<KeyringProvider>>,
+    precedence: Precedence,
+    namespaced: bool,
+    rotation_fallback: Option<String>,
+    name_fallbacks: Vec<String>,
+    json_parts: Vec<String>,
+    tenant_key: Option<String>,
+    prefix: Option<String>,
+    mirror_target: Option<Keyring>,
+    privilege_check: fn() -> error::Result<bool>,
+    profile_selector: Option<ProfileSelector>,
+    also_default: bool,
+    version_pointer: Option<String>,
+    append_key: Option<String>,
+    encoding: Encoding,
+    #[cfg(feature = "encoding")]
+    charset: Charset,
+    strip_newline: bool,
+    strip_bom: bool,
+    list_delimiter: Option<char>,
+    cache_ttl: Option<Duration>,
+    cache: Arc<Mutex<HashMap<Option<String>, CachedSecret>>>,
+    negative_ttl: Option<Duration>,
+    misses: Arc<Mutex<HashMap<Option<String>, Instant>>>,
+    seen_digest: Arc<Mutex<Option<[u8; 32]>>>,
+    clock: Arc<dyn Clock>,
+    read_counter: Arc<AtomicUsize>,
+    read_rng: Arc<Mutex<u64>>,
+    breaker: Option<Arc<CircuitBreaker>>,
+    timeout: Option<Duration>,
+    read_worker: Arc<ReadWorker>,
+    key_separator: Option<String>,
+}
+
+/// Consecutive failure counts of each keyring, see
+/// [`KeyringProvider::with_circuit_breaker`].
+struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    states: Mutex<HashMap<Keyring, BreakerState>>,
+}
+
+#[derive(Clone, Default)]
+struct BreakerState {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+type ReadJob = Box<dyn FnOnce() + Send>;
+
+/// The background thread that runs keyring reads under
+/// [`KeyringProvider::with_timeout`], started on first use.
+///
+/// Each read waits on an idle worker, so its timeout covers only its own
+/// read. A read arriving while the worker is busy, whether on a slow read or
+/// one already abandoned after a timeout, starts a replacement worker; the
+/// old one exits once its read returns.
+#[derive(Default)]
+struct ReadWorker {
+    jobs: Mutex<Option<(std::sync::mpsc::Sender<ReadJob>, Arc<AtomicBool>)>>,
+}
+
+impl ReadWorker {
+    /// Run `read` on the worker and wait up to `timeout` for its result.
+    fn read<F>(&self, timeout: Duration, read: F) -> std::result::Result<Vec<u8>, KeyringError>
+    where
+        F: FnOnce() -> std::result::Result<Vec<u8>, KeyringError> + Send + 'static,
+    {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        {
+            let mut jobs = self.jobs.lock().expect("read worker poisoned");
+            let idle = match jobs.as_ref() {
+                Some((_, busy)) => !busy.load(Ordering::SeqCst),
+                None => false,
+            };
+            if !idle {
+                let (jobs_sender, queued) = std::sync::mpsc::channel::<ReadJob>();
+                let busy = Arc::new(AtomicBool::new(false));
+                let worker_busy = busy.clone();
+                std::thread::spawn(move || {
+                    for job in queued {
+                        std::panic::catch_unwind(std::panic::AssertUnwindSafe(job)).ok();
+                        worker_busy.store(false, Ordering::SeqCst);
+                    }
+                });
+                *jobs = Some((jobs_sender, busy));
+            }
+            let (jobs_sender, busy) = jobs.as_ref().expect("read worker started");
+            busy.store(true, Ordering::SeqCst);
+            let done = busy.clone();
+            let job: ReadJob = Box::new(move || {
+                let result = read();
+                // Free the worker before answering so the next read reuses it.
+                done.store(false, Ordering::SeqCst);
+                sender.send(result).ok();
+            });
+            jobs_sender.send(job).ok();
+        }
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Err(KeyringError::Timeout(timeout)),
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                Err(KeyringError::BackendError("keyring read panicked".into()))
+            }
+        }
+    }
+}
+
+/// A looked-up secret and when it was fetched, keyed by credential name or
+/// `None` for the provider's own credential.
+type CachedSecret = (Instant, Resolution, Vec<u8>);
+
+/// A secret from the fallback chain, with its keyring resolution if any.
+type ChainSecret = (Option<Resolution>, Vec<u8>);
+
+/// Source of the current time for cache expiry.
+///
+/// Tests can supply their own clock with
+/// [`KeyringProvider::with_clock`] to advance time deterministically.
+pub trait Clock: Send + Sync {
+    /// The current instant.
+    fn now(&self) -> Instant;
+}
+
+/// The [`Clock`] reading [`Instant::now`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+type ConfigMapper = Arc<dyn Fn(KeyringConfig) -> KeyringConfig + Send + Sync>;
+type ProfileSelector = Arc<dyn Fn(&Figment) -> Profile + Send + Sync>;
+type ConfigReloader = Arc<dyn Fn() -> Figment + Send + Sync>;
+type AccessHook = Arc<dyn Fn(&Keyring, &str, &str) + Send + Sync>;
+type ErrorMapper =
+    Arc<dyn Fn(KeyringError) -> std::result::Result<Option<Value>, Error> + Send + Sync>;
+#[cfg(feature = "generate")]
+type SharedRng = Arc<Mutex<dyn rand::Rng + Send>>;
+
+/// Whether a provider's values should win over or defer to the values
+/// already in a figment, see [`KeyringProvider::precedence_hint`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Precedence {
+    /// Keyring values replace conflicting values (default)
+    #[default]
+    Override,
+    /// Existing values are kept; keyring values only fill gaps
+    Fallback,
+}
+
+/// Where a provider found its secret, as reported by
+/// [`KeyringProvider::resolve`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Resolution {
+    /// Keyring that held the secret
+    pub keyring: Keyring,
+    /// Credential name the secret was stored under
+    pub credential: String,
+    /// Whether the secret came from the old name of a rotation or a name
+    /// fallback
+    pub fallback: bool,
+}
+
+/// Reachability of each keyring, as reported by
+/// [`KeyringProvider::self_test`].
+#[derive(Debug)]
+pub struct SelfTestReport {
+    /// Probe outcome for every keyring, in search order
+    pub keyrings: Vec<KeyringStatus>,
+}
+
+impl SelfTestReport {
+    /// Whether every probed keyring was reachable.
+    pub fn all_reachable(&self) -> bool {
+        self.keyrings.iter().all(|status| status.error.is_none())
+    }
+}
+
+/// Probe outcome for one keyring.
+#[derive(Debug)]
+pub struct KeyringStatus {
+    /// Keyring that was probed
+    pub keyring: Keyring,
+    /// Why the keyring is unreachable, or `None` when it answered
+    pub error: Option<KeyringError>,
+}
+
+/// The secrets a provider depends on, as reported by
+/// [`KeyringProvider::descriptor`].
+///
+/// Holds configuration only, never secret values, so it can be serialized
+/// into reports of what an application needs from the keyring.
+#[derive(Clone, Debug, Serialize)]
+pub struct ProviderDescriptor {
+    /// Service the credentials are stored under
+    pub service: String,
+    /// Keyrings searched, in search order
+    pub keyrings: Vec<Keyring>,
+    /// Credential name looked up
+    pub credential: String,
+    /// Config key the secret is emitted under, when it differs from the
+    /// credential name
+    pub config_key: Option<String>,
+    /// Manifest of credentials loaded instead of `credential`
+    pub credentials: Vec<ManifestEntry>,
+    /// Credential names merged as JSON fragments
+    pub json_parts: Vec<String>,
+    /// Name prefix of credentials loaded by enumeration
+    pub prefix: Option<String>,
+    /// Profile the secrets are emitted into
+    pub profile: String,
+    /// Whether a missing secret is tolerated
+    pub optional: bool,
+    /// Whether writes through the provider are rejected
+    pub read_only: bool,
+    /// Encoding the secret is stored in
+    pub encoding: Encoding,
 }
 
+/// The UTF-8 encoding of U+FEFF, removed by [`KeyringProvider::strip_bom`].
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Marks a string value as a reference to a keyring secret, see
+/// [`KeyringProvider::resolve_references`].
+const REFERENCE_PREFIX: &str = "keyring:";
+
+/// Credential name looked up by [`KeyringProvider::self_test`].
+const SELF_TEST_PROBE: &str = "__figment_keyring_self_test__";
+
 impl KeyringProvider {
     pub fn configured_by(config_figment: Figment, credential_name: &str) -> Self {
+        Self::configured_by_arc(Arc::new(config_figment), credential_name)
+    }
+
+    /// Like [`configured_by`](Self::configured_by), sharing a config figment
+    /// the caller already holds in an [`Arc`] instead of moving it.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use figment2::Figment;
+    /// # use figment_keyring::KeyringProvider;
+    /// let config_figment = Arc::new(Figment::new());
+    /// let api_key = KeyringProvider::configured_by_arc(config_figment.clone(), "api_key");
+    /// let db_password = KeyringProvider::configured_by_arc(config_figment, "db_password");
+    /// ```
+    pub fn configured_by_arc(config_figment: Arc<Figment>, credential_name: &str) -> Self {
         Self {
-            config_figment: Arc::new(config_figment),
+            config_figment,
             credential_name: credential_name.into(),
             config_key: None,
             profile: None,
+            entry_factory: None,
+            entry_search: None,
+            profile_delimiter: None,
+            profile_suffixes: Vec::new(),
+            snapshot_export: false,
+            snapshot: None,
+            dotenv: false,
+            enabled_key: None,
+            credential_overrides: Arc::new(HashMap::new()),
+            config_mapper: None,
+            config_retry: None,
+            error_mapper: None,
+            help: None,
+            #[cfg(feature = "generate")]
+            rng: None,
+            access_hook: None,
+            consume: false,
+            strict_errors: false,
+            naming: None,
+            swap_names: false,
+            access_context: None,
+            source_key: false,
+            credential_form: false,
+            fallback_chain: Vec::new(),
+            or_else: None,
+            precedence: Precedence::Override,
+            namespaced: false,
+            rotation_fallback: None,
+            name_fallbacks: Vec::new(),
+            json_parts: Vec::new(),
+            tenant_key: None,
+            prefix: None,
+            mirror_target: None,
+            privilege_check: keyring_config::backend::is_elevated,
+            profile_selector: None,
+            also_default: false,
+            version_pointer: None,
+            append_key: None,
+            encoding: Encoding::Raw,
+            #[cfg(feature = "encoding")]
+            charset: Charset::UTF_8,
+            strip_newline: false,
+            strip_bom: false,
+            list_delimiter: None,
+            cache_ttl: None,
+            cache: Arc::default(),
+            negative_ttl: None,
+            misses: Arc::default(),
+            seen_digest: Arc::default(),
+            clock: Arc::new(SystemClock),
+            read_counter: Arc::default(),
+            read_rng: Arc::new(Mutex::new(random_seed())),
+            breaker: None,
+            timeout: None,
+            read_worker: Arc::default(),
+            key_separator: Some(".".into()),
         }
     }
 
@@ -155,9 +550,15 @@ impl KeyringProvider {
     pub fn focused(&self, path: &str) -> Self {
         Self {
             config_figment: Arc::new(self.config_figment.focus(path)),
-            credential_name: self.credential_name.clone(),
-            config_key: self.config_key.clone(),
-            profile: self.profile.clone(),
+            cache: Arc::default(),
+            misses: Arc::default(),
+            seen_digest: Arc::default(),
+            config_retry: self.config_retry.clone().map(|(attempts, reload)| {
+                let path = path.to_string();
+                let focused: ConfigReloader = Arc::new(move || reload().focus(&path));
+                (attempts, focused)
+            }),
+            ..self.clone()
         }
     }
 
@@ -165,20 +566,131 @@ impl KeyringProvider {
         let config = KeyringConfig {
             service: service.into(),
             keyrings: vec![Keyring::User],
-            optional: false,
+            ..Default::default()
         };
-        let figment = Figment::from(Serialized::defaults(config));
-        Self::configured_by(figment, credential_name)
+        Self::configured_by(config.into(), credential_name)
     }
 
     pub fn system(service: &str, credential_name: &str) -> Self {
         let config = KeyringConfig {
             service: service.into(),
             keyrings: vec![Keyring::System],
-            optional: false,
+            ..Default::default()
+        };
+        Self::configured_by(config.into(), credential_name)
+    }
+
+    /// Load every credential of `service` whose name starts with `prefix`.
+    ///
+    /// The entries are enumerated from each keyring and emitted with the
+    /// prefix stripped, so `feature.beta` and `feature.dark_mode` loaded with
+    /// prefix `feature.` become the keys `beta` and `dark_mode`. When a
+    /// credential exists in several keyrings, the first one searched wins.
+    /// Stores that cannot enumerate entries fail with
+    /// [`KeyringError::Unsupported`].
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::KeyringProvider;
+    /// let provider = KeyringProvider::load_prefix("myapp", "feature.").as_key("features");
+    /// ```
+    pub fn load_prefix(service: &str, prefix: &str) -> Self {
+        let mut provider = Self::new(service, prefix);
+        provider.prefix = Some(prefix.into());
+        provider
+    }
+
+    /// Build a provider from environment variables alone.
+    ///
+    /// With prefix `MYAPP`, the config is read from:
+    ///
+    /// * `MYAPP_CREDENTIAL`: the credential name (required)
+    /// * `MYAPP_SERVICE`: the service name (required)
+    /// * `MYAPP_KEYRINGS`: comma-separated keyrings, e.g. `user,system`
+    /// * `MYAPP_OPTIONAL`: `true` or `false`
+    ///
+    /// Unset variables other than the credential and service keep the
+    /// config defaults.
+    pub fn from_env(prefix: &str) -> error::Result<Self> {
+        let var = |name: &str| std::env::var(format!("{}_{}", prefix, name)).ok();
+        let required = |name: &str| {
+            var(name)
+                .ok_or_else(|| KeyringError::ConfigError(format!("{}_{} is not set", prefix, name)))
+        };
+
+        let mut config = KeyringConfig {
+            service: required("SERVICE")?,
+            ..Default::default()
+        };
+        if let Some(keyrings) = var("KEYRINGS") {
+            config.keyrings = keyrings
+                .split(',')
+                .map(str::trim)
+                .filter(|keyring| !keyring.is_empty())
+                .map(Keyring::from)
+                .collect();
+        }
+        if let Some(optional) = var("OPTIONAL") {
+            config.optional = match optional.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" => true,
+                "false" | "0" | "no" | "" => false,
+                other => {
+                    return Err(KeyringError::ConfigError(format!(
+                        "{}_OPTIONAL must be true or false, not '{}'",
+                        prefix, other
+                    )));
+                }
+            };
+        }
+        Ok(Self::configured_by(config.into(), &required("CREDENTIAL")?))
+    }
+
+    /// Create a provider that serves a snapshot taken with
+    /// [`export_snapshot`](Self::export_snapshot) instead of accessing any keyring.
+    ///
+    /// The snapshot maps profile names to the dict emitted for that profile.
+    pub fn from_snapshot(snapshot: Dict) -> Self {
+        let mut provider = Self::configured_by(Figment::new(), "");
+        provider.snapshot = Some(Arc::new(snapshot));
+        provider
+    }
+
+    /// Create a provider for secrets that don't share a service, each read
+    /// from the keyring, service and credential name its [`EntrySpec`]
+    /// gives and emitted under its own key.
+    ///
+    /// Missing secrets fail the provider unless their spec, or the config,
+    /// is `optional`. This is the provider [`configured_by`](Self::configured_by)
+    /// builds for a config whose `secrets` list holds `specs` and which
+    /// otherwise keeps the [`KeyringConfig`] defaults. To read the list and
+    /// the other settings from the application's config instead, use
+    /// `configured_by` with a `secrets` list, as shown for [`EntrySpec`]; the
+    /// specs then take the place of the config's `service` and `keyrings`.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::{EntrySpec, Keyring, KeyringProvider};
+    /// let provider = KeyringProvider::from_entries(vec![
+    ///     EntrySpec {
+    ///         keyring: Keyring::System,
+    ///         service: "billing".into(),
+    ///         credential: "stripe_key".into(),
+    ///         config_key: Some("payments.api_key".into()),
+    ///         optional: false,
+    ///     },
+    ///     EntrySpec {
+    ///         keyring: Keyring::User,
+    ///         service: "mailer".into(),
+    ///         credential: "smtp_password".into(),
+    ///         config_key: None,
+    ///         optional: true,
+    ///     },
+    /// ]);
+    /// ```
+    pub fn from_entries(specs: Vec<EntrySpec>) -> Self {
+        let config = KeyringConfig {
+            secrets: specs,
+            ..Default::default()
         };
-        let figment = Figment::from(Serialized::defaults(config));
-        Self::configured_by(figment, credential_name)
+        Self::configured_by(config.into(), "")
     }
 
     pub fn as_key(mut self, key: &str) -> Self {
@@ -190,146 +702,6278 @@ impl KeyringProvider {
         self.profile = Some(profile);
         self
     }
-}
 
-impl Provider for KeyringProvider {
-    fn metadata(&self) -> Metadata {
-        Metadata::named("keyring")
+    /// Choose the profile at `data()` time instead of fixing it up front.
+    ///
+    /// The selector is called with the config figment on every `data()` and
+    /// takes precedence over [`with_profile`](Self::with_profile).
+    ///
+    /// ```rust,no_run
+    /// # use figment2::Profile;
+    /// # use figment_keyring::KeyringProvider;
+    /// let provider = KeyringProvider::new("myapp", "api_key")
+    ///     .profile_selector(|_| Profile::from_env_or("APP_PROFILE", Profile::Default));
+    /// ```
+    pub fn profile_selector<F>(mut self, selector: F) -> Self
+    where
+        F: Fn(&Figment) -> Profile + Send + Sync + 'static,
+    {
+        self.profile_selector = Some(Arc::new(selector));
+        self
     }
 
-    fn data(&self) -> std::result::Result<Map<Profile, Dict>, Error> {
-        let config: KeyringConfig = self
-            .config_figment
-            .extract()
-            .map_err(|e| Error::from(format!("keyring config: {}", e)))?;
-
-        let secret = self.search_keyrings(&config)?;
+    /// Also emit the secret into the [`Profile::Default`] profile.
+    ///
+    /// The dict for the selected profile is copied into the default profile,
+    /// for code that reads defaults even when another profile is active.
+    pub fn also_default(mut self) -> Self {
+        self.also_default = true;
+        self
+    }
 
-        let key = self.config_key.as_ref().unwrap_or(&self.credential_name);
+    /// Emit the secret into the [`Profile::Global`] profile.
+    ///
+    /// Figment applies global values to every selected profile, so the secret
+    /// is visible no matter which profile the application extracts.
+    pub fn for_all_profiles(self) -> Self {
+        self.with_profile(Profile::Global)
+    }
 
-        let profile = self.profile.clone().unwrap_or_default();
-        let mut dict = Dict::new();
+    /// Parse the secret as a `.env`-style blob of `KEY=VALUE` lines.
+    ///
+    /// Each line becomes its own key in the profile dict instead of the secret
+    /// being emitted under a single key. Blank lines and `#` comments are
+    /// skipped and values may be quoted. A malformed line fails with an error
+    /// naming its line number.
+    pub fn as_dotenv(mut self) -> Self {
+        self.dotenv = true;
+        self
+    }
 
-        match secret {
-            Some(value) => {
-                dict.insert(key.clone(), Value::from(value));
-            }
-            None if config.optional => {}
-            None => {
-                return Err(Error::from(format!(
-                    "secret '{}' not found in any keyring",
-                    self.credential_name
-                )));
-            }
-        }
+    /// Only access the keyring when a boolean flag in the config figment is set.
+    ///
+    /// The flag at `key` is read from the config figment during `data()`. When
+    /// it is `false` or missing, the provider emits an empty dict without
+    /// touching any keyring, so users who disabled the feature are never
+    /// prompted.
+    ///
+    /// ```rust,no_run
+    /// # use figment2::Figment;
+    /// # use figment_keyring::KeyringProvider;
+    /// # let config_figment = Figment::new();
+    /// let provider = KeyringProvider::configured_by(config_figment, "api_key")
+    ///     .enabled_if("use_keyring");
+    /// ```
+    pub fn enabled_if(mut self, key: &str) -> Self {
+        self.enabled_key = Some(key.into());
+        self
+    }
 
-        let mut map = Map::new();
-        map.insert(profile, dict);
-        Ok(map)
+    /// Look up a different credential name in specific keyrings.
+    ///
+    /// Keyrings missing from `overrides` use the provider's credential name.
+    /// The emitted config key is unaffected, which helps during a rename where
+    /// a legacy keyring still holds the secret under its old name.
+    ///
+    /// ```rust,no_run
+    /// # use std::collections::HashMap;
+    /// # use figment2::Figment;
+    /// # use figment_keyring::{Keyring, KeyringProvider};
+    /// # let config_figment = Figment::new();
+    /// let provider = KeyringProvider::configured_by(config_figment, "api_token")
+    ///     .credential_overrides(HashMap::from([(Keyring::from("legacy"), "api_key".into())]));
+    /// ```
+    pub fn credential_overrides(mut self, overrides: HashMap<Keyring, String>) -> Self {
+        self.credential_overrides = Arc::new(overrides);
+        self
     }
-}
 
-impl KeyringProvider {
-    fn search_keyrings(
-        &self,
-        config: &KeyringConfig,
-    ) -> std::result::Result<Option<String>, Error> {
-        for keyring in &config.keyrings {
-            match self.get_from_keyring(keyring, &config.service, &self.credential_name) {
-                Ok(secret) => return Ok(Some(secret)),
-                Err(KeyringError::NotFound(_)) => continue,
-                Err(e) => {
-                    if config.optional {
-                        continue;
-                    } else {
-                        return Err(Error::from(e.to_string()));
-                    }
-                }
-            }
-        }
-        Ok(None)
+    /// Adjust the keyring config after it is extracted and before any lookup.
+    ///
+    /// This is an escape hatch for changes that depend on runtime state, such
+    /// as appending a region suffix to the service.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::KeyringProvider;
+    /// let provider = KeyringProvider::new("myapp", "api_key").map_config(|mut config| {
+    ///     config.service.push_str("-eu");
+    ///     config
+    /// });
+    /// ```
+    pub fn map_config<F>(mut self, f: F) -> Self
+    where
+        F: Fn(KeyringConfig) -> KeyringConfig + Send + Sync + 'static,
+    {
+        self.config_mapper = Some(Arc::new(f));
+        self
     }
 
-    fn get_from_keyring(
-        &self,
-        keyring: &Keyring,
-        service: &str,
-        username: &str,
-    ) -> std::result::Result<String, KeyringError> {
-        keyring_config::backend::get_secret(keyring, service, username)
+    /// Retry a failed config extraction up to `attempts` times.
+    ///
+    /// A figment reads its providers once, when they are merged, so each
+    /// retry extracts from a fresh figment built by `reload`. This covers
+    /// config drawn from slow or remote sources and is separate from the
+    /// keyring lookup retries of `transient_error_patterns`.
+    ///
+    /// ```rust,no_run
+    /// # use figment2::{Figment, providers::Env};
+    /// # use figment_keyring::KeyringProvider;
+    /// let load = || Figment::from(Env::prefixed("MYAPP_KEYRING_"));
+    /// let provider = KeyringProvider::configured_by(load(), "api_key").retry_config(2, load);
+    /// ```
+    pub fn retry_config<F>(mut self, attempts: u32, reload: F) -> Self
+    where
+        F: Fn() -> Figment + Send + Sync + 'static,
+    {
+        self.config_retry = Some((attempts, Arc::new(reload)));
+        self
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Decide how a failure in [`Provider::data`] reaches figment.
+    ///
+    /// `f` sees the error before it is turned into a figment error. It can
+    /// return a replacement value to emit under the provider's key, `None`
+    /// to emit nothing, or the figment error to fail with.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::{KeyringProvider, error::KeyringError};
+    /// let provider = KeyringProvider::new("myapp", "api_key").map_error(|e| match e {
+    ///     KeyringError::NotFound(_) => Ok(None),
+    ///     e => Err(e.to_string().into()),
+    /// });
+    /// ```
+    pub fn map_error<F>(mut self, f: F) -> Self
+    where
+        F: Fn(KeyringError) -> std::result::Result<Option<Value>, Error> + Send + Sync + 'static,
+    {
+        self.error_mapper = Some(Arc::new(f));
+        self
+    }
 
-    #[test]
-    fn test_keyring_from_str() {
-        assert_eq!(Keyring::from("user"), Keyring::User);
-        assert_eq!(Keyring::from("system"), Keyring::System);
-        assert_eq!(
-            Keyring::from("custom-keyring"),
-            Keyring::Named("custom-keyring".into())
-        );
+    /// Append `hint`, such as a docs URL or a login command, to the figment
+    /// error reported when a required secret is missing.
+    ///
+    /// The hint is also appended to an error returned by a
+    /// [`map_error`](Self::map_error) hook for a missing secret. Other
+    /// errors are left as they are.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::KeyringProvider;
+    /// let provider = KeyringProvider::new("myapp", "api_key")
+    ///     .help("see https://example.com/docs/setup or run `myapp login`");
+    /// ```
+    pub fn help(mut self, hint: &str) -> Self {
+        self.help = Some(hint.into());
+        self
     }
 
-    #[test]
-    fn test_keyring_default() {
-        assert_eq!(Keyring::default(), Keyring::User);
+    /// Call `hook` with the keyring, service and credential name right
+    /// before each secret is read from a keyring.
+    ///
+    /// On platforms where access can show a prompt, this lets a UI explain
+    /// what is being requested. The hook runs before the fetch, so it never
+    /// sees the secret, and again for every retry.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::KeyringProvider;
+    /// let provider = KeyringProvider::new("myapp", "api_key").before_access(|_, service, _| {
+    ///     eprintln!("requesting access to {}", service);
+    /// });
+    /// ```
+    pub fn before_access<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Keyring, &str, &str) + Send + Sync + 'static,
+    {
+        self.access_hook = Some(Arc::new(hook));
+        self
     }
 
-    #[test]
-    fn test_keyring_provider_new() {
-        let provider = KeyringProvider::new("test-app", "test-key");
-        assert_eq!(provider.credential_name, "test-key");
+    /// Name keyring entries the way another tool does, so secrets it wrote
+    /// can be read.
+    ///
+    /// `naming` maps the service and each credential name to the service,
+    /// username and modifiers of the backend entry; see [`naming`] for the
+    /// strategies provided. Enumerating features such as
+    /// [`load_prefix`](Self::load_prefix) still list entries by the
+    /// configured service.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::{KeyringProvider, naming::GhCli};
+    /// let provider = KeyringProvider::new("github.com", "octocat").with_naming(Box::new(GhCli));
+    /// ```
+    pub fn with_naming(mut self, naming: Box<dyn NamingStrategy>) -> Self {
+        self.naming = Some(Arc::from(naming));
+        self
     }
 
-    #[test]
-    fn test_keyring_provider_system() {
-        let provider = KeyringProvider::system("test-app", "test-key");
-        assert_eq!(provider.credential_name, "test-key");
+    /// Tag every keyring access with a correlation id, for stores that
+    /// audit access.
+    ///
+    /// The id is passed to the backend as the
+    /// [`ACCESS_REASON_MODIFIER`](keyring_config::backend::ACCESS_REASON_MODIFIER)
+    /// entry modifier. Only use it with stores that accept that modifier; stores
+    /// that reject unknown modifiers fail the lookup.
+    pub fn with_context(mut self, id: &str) -> Self {
+        self.access_context = Some(id.into());
+        self
     }
 
-    #[test]
-    fn test_keyring_provider_as_key() {
-        let provider = KeyringProvider::new("test-app", "test-key").as_key("custom.config.key");
-        assert_eq!(provider.config_key, Some("custom.config.key".into()));
+    /// Pass the credential name to the backend as the service and the
+    /// service as the username, for stores written by tools that swap the
+    /// two roles.
+    ///
+    /// The swap applies after any [`with_naming`](Self::with_naming)
+    /// strategy. Enumerating entries, as [`load_prefix`](Self::load_prefix)
+    /// does, still searches by the configured service.
+    pub fn swap_service_username(mut self) -> Self {
+        self.swap_names = true;
+        self
     }
 
-    #[test]
-    fn test_keyring_provider_with_profile() {
-        let profile = Profile::from("production");
-        let provider = KeyringProvider::new("test-app", "test-key").with_profile(profile.clone());
-        assert_eq!(provider.profile, Some(profile));
+    /// Also emit `{key}_source` holding the name of the keyring the secret
+    /// came from, such as `"user"`, to debug config layering.
+    ///
+    /// Only the keyring's identity is emitted, never the secret. Providers
+    /// that load several secrets do not emit it.
+    pub fn include_source_key(mut self) -> Self {
+        self.source_key = true;
+        self
     }
 
-    #[test]
-    fn test_keyring_provider_focused() {
-        let config_figment = Figment::new();
-        let provider = KeyringProvider::configured_by(config_figment, "api_key");
-        let focused_provider = provider.focused("keyring");
-        assert_eq!(focused_provider.credential_name, "api_key");
-        assert_eq!(focused_provider.config_key, None);
-        assert_eq!(focused_provider.profile, None);
+    /// Emit the secret as a credential object `{username, password}` under
+    /// the config key instead of a bare string.
+    ///
+    /// The username is the one the backend reports for the entry the secret
+    /// was read from; backends that only store a password report the name it
+    /// was looked up by, which is also used for secrets from non-keyring
+    /// sources of a [`fallback_chain`](Self::fallback_chain).
+    ///
+    /// ```rust,no_run
+    /// # use figment2::Figment;
+    /// # use figment_keyring::KeyringProvider;
+    /// let figment = Figment::new()
+    ///     .merge(KeyringProvider::new("myapp", "database").as_credential());
+    /// let username: String = figment.extract_inner("database.username").unwrap();
+    /// ```
+    pub fn as_credential(mut self) -> Self {
+        self.credential_form = true;
+        self
     }
 
-    #[test]
-    fn test_keyring_provider_focused_preserves_config_key() {
-        let config_figment = Figment::new();
-        let provider =
-            KeyringProvider::configured_by(config_figment, "api_key").as_key("custom_key");
-        let focused_provider = provider.focused("keyring");
-        assert_eq!(focused_provider.config_key, Some("custom_key".into()));
+    /// Delete the secret from the keyring it was found in once it has been
+    /// read, so later reads miss it.
+    ///
+    /// This suits one-time bootstrap tokens. A failed delete, including one
+    /// refused by `read_only`, is logged and does not fail the read.
+    pub fn consume_once(mut self) -> Self {
+        self.consume = true;
+        self
     }
 
-    #[test]
-    fn test_keyring_provider_focused_preserves_profile() {
-        let config_figment = Figment::new();
-        let profile = Profile::from("production");
-        let provider =
+    /// Look for the secret in `sources`, in order, instead of only the
+    /// keyrings.
+    ///
+    /// The first source holding a secret provides it. Leaving
+    /// [`FallbackSource::Keyring`] out skips the keyrings entirely, and a
+    /// chain of only `Keyring` behaves as if no chain were set. Missing
+    /// environment variables and files are passed over; keyring errors stop
+    /// the search as usual. When no source holds the secret the provider
+    /// fails unless the config sets `optional`. The chain applies to
+    /// [`Provider::data`] and [`secret`](Self::secret).
+    ///
+    /// ```rust,no_run
+    /// # use figment2::Figment;
+    /// # use figment_keyring::{FallbackSource, KeyringProvider};
+    /// let chain = if cfg!(debug_assertions) {
+    ///     vec![
+    ///         FallbackSource::File(".secrets/api_key".into()),
+    ///         FallbackSource::Env("API_KEY".into()),
+    ///         FallbackSource::Keyring,
+    ///     ]
+    /// } else {
+    ///     vec![FallbackSource::Keyring]
+    /// };
+    /// let provider = KeyringProvider::configured_by(Figment::new(), "api_key")
+    ///     .fallback_chain(chain);
+    /// ```
+    pub fn fallback_chain(mut self, sources: Vec<FallbackSource>) -> Self {
+        self.fallback_chain = sources;
+        self
+    }
+
+    /// Fall back to the `other` provider when this one supplies no value.
+    ///
+    /// Where [`fallback_chain`](Self::fallback_chain) combines keyrings,
+    /// this combines whole providers, each with its own service and config,
+    /// e.g. a personal keyring then a shared team one. `other` is only read
+    /// when this provider's secret is missing, or it emits nothing because
+    /// the config sets `optional`; any other error is reported as usual. A
+    /// provider switched off by [`enabled_if`](Self::enabled_if) emits
+    /// nothing and does not fall back. Calling `or` again appends to the end
+    /// of the chain. Applies to [`Provider::data`],
+    /// [`data_shape`](Self::data_shape) and
+    /// [`export_snapshot`](Self::export_snapshot).
+    ///
+    /// ```rust,no_run
+    /// # use figment2::Figment;
+    /// # use figment_keyring::KeyringProvider;
+    /// let figment = Figment::new().merge(
+    ///     KeyringProvider::new("myapp-personal", "api_key")
+    ///         .or(KeyringProvider::new("myapp-team", "api_key")),
+    /// );
+    /// ```
+    pub fn or(mut self, other: KeyringProvider) -> Self {
+        let next = match self.or_else.take() {
+            Some(next) => next.or(other),
+            None => other,
+        };
+        self.or_else = Some(Box::new(next));
+        self
+    }
+
+    /// Propagate backend errors even when the config sets `optional`.
+    ///
+    /// `optional` then only tolerates a secret that is missing from every
+    /// keyring; a denied or failing keyring is reported instead of being
+    /// passed over. Equivalent to setting `strict_errors` in the config.
+    pub fn strict_errors(mut self) -> Self {
+        self.strict_errors = true;
+        self
+    }
+
+    /// Nest the emitted keys under the service name.
+    ///
+    /// With service `myapp`, the secret is emitted as `myapp.api_key`, so
+    /// providers for different services never collide in the merged figment.
+    pub fn namespaced_by_service(mut self) -> Self {
+        self.namespaced = true;
+        self
+    }
+
+    /// Read a credential that is being rotated from `old_name` to `new_name`.
+    ///
+    /// Every keyring is searched for `new_name` first, and `old_name` is only
+    /// tried when the new name is found nowhere. The secret is emitted under
+    /// the new name either way; [`resolve`](Self::resolve) reports which name
+    /// was used.
+    pub fn with_rotation(mut self, new_name: &str, old_name: &str) -> Self {
+        self.credential_name = new_name.into();
+        self.rotation_fallback = Some(old_name.into());
+        self
+    }
+
+    /// Try `names`, in order, in each keyring where the credential name is
+    /// not found, before moving on to the next keyring.
+    ///
+    /// This eases gradual renames: unlike
+    /// [`with_rotation`](Self::with_rotation), a keyring holding an old name
+    /// is used before a later keyring holding the current one. The secret is
+    /// emitted under the credential name either way; [`resolve`](Self::resolve)
+    /// reports which name was used.
+    pub fn with_name_fallbacks(mut self, names: Vec<&str>) -> Self {
+        self.name_fallbacks = names.into_iter().map(String::from).collect();
+        self
+    }
+
+    /// Copy the secret into `keyring` after it is read from another keyring.
+    ///
+    /// Once a lookup succeeds, the secret is written to `keyring` unless that
+    /// keyring already holds the credential, e.g. to copy a secret from the
+    /// `system` keyring into the `user` keyring on first read. A failed
+    /// mirror write is logged and never fails the read. Mirroring is skipped
+    /// when the config sets `read_only` or gives `keyring` the
+    /// [`Read`](KeyringRole::Read) role; give it the
+    /// [`Write`](KeyringRole::Write) role to keep it out of lookups.
+    pub fn mirror_to(mut self, keyring: Keyring) -> Self {
+        self.mirror_target = Some(keyring);
+        self
+    }
+
+    /// Decode secrets stored in a text encoding such as hex or base64.
+    ///
+    /// The decoded bytes are then handled like any other secret, so binary
+    /// data still follows the config's `on_invalid_utf8` policy.
+    /// [`Encoding::Auto`] guesses the encoding from the characters used;
+    /// prefer naming it when it is known, as a short raw secret may happen to
+    /// look like hex or base64.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Decode secrets whose bytes are in `charset`, such as UTF-16 or a
+    /// Windows code page, instead of UTF-8.
+    ///
+    /// The charset applies after any [`with_encoding`](Self::with_encoding)
+    /// decoding. A secret that is not valid in `charset` fails with a parse
+    /// error.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::{Charset, KeyringProvider};
+    /// let provider = KeyringProvider::new("myapp", "api_key").with_charset(Charset::UTF_16LE);
+    /// ```
+    #[cfg(feature = "encoding")]
+    pub fn with_charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Keep a found secret for `ttl` instead of reading the keyring on every
+    /// lookup.
+    ///
+    /// The cache is shared by clones of the provider and cleared by
+    /// [`store`](Self::store) and [`delete`](Self::delete). A secret that is
+    /// not found is never cached.
+    pub fn cache_for(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Remember for `ttl` that a secret was not found, so repeated lookups of
+    /// an absent optional secret don't reach the keyring.
+    ///
+    /// The miss is recorded once every keyring has been searched. Like the
+    /// [`cache_for`](Self::cache_for) cache, recorded misses are shared by
+    /// clones of the provider and cleared by [`store`](Self::store) and
+    /// [`delete`](Self::delete). `ttl` is independent of the positive TTL
+    /// and typically shorter.
+    pub fn with_negative_cache(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = Some(ttl);
+        self
+    }
+
+    /// Look up every secret the provider needs now and keep them in the
+    /// cache, so that later [`Provider::data`] calls don't touch the keyring.
+    ///
+    /// This front-loads any keyring prompts to a moment of the caller's
+    /// choosing. The provider's credential, or each entry of a credentials
+    /// manifest or [`merge_json_parts`](Self::merge_json_parts), is fetched
+    /// with the usual error policy. The provider's credential is searched
+    /// through the [`fallback_chain`](Self::fallback_chain) and checked
+    /// against `expected_sha256` just as [`Provider::data`] reads it, so
+    /// only a secret from a keyring is cached. Prefetched secrets expire after the
+    /// [`cache_for`](Self::cache_for) TTL when one is set and are otherwise
+    /// kept until [`store`](Self::store) or [`delete`](Self::delete).
+    ///
+    /// Enumerating providers such as [`load_prefix`](Self::load_prefix) and
+    /// [`split_profile_on`](Self::split_profile_on) are not cached and fail
+    /// with [`KeyringError::Unsupported`], as do providers with a `secrets`
+    /// list such as [`from_entries`](Self::from_entries).
+    pub fn prefetch(&self) -> error::Result<()> {
+        if self.snapshot.is_some() || !self.enabled()? {
+            return Ok(());
+        }
+        let enumerated = self.profile_delimiter.is_some() && self.profile_suffixes.is_empty();
+        if self.prefix.is_some() || enumerated {
+            return Err(KeyringError::Unsupported(
+                "prefetch of enumerated credentials".into(),
+            ));
+        }
+
+        let config = self.extract_config()?;
+        if !config.secrets.is_empty() {
+            return Err(KeyringError::Unsupported(
+                "prefetch of a secrets list".into(),
+            ));
+        }
+        self.check_single_hash(&config)?;
+        let names: Vec<(String, bool)> = if !self.profile_suffixes.is_empty() {
+            self.suffixed_names()
+                .map(|(_, name)| (name, config.optional))
+                .collect()
+        } else if !config.credentials.is_empty() {
+            config
+                .credentials
+                .iter()
+                .map(|entry| (entry.name.clone(), entry.is_optional(&config)))
+                .collect()
+        } else {
+            self.json_parts
+                .iter()
+                .map(|part| (part.clone(), config.optional))
+                .collect()
+        };
+        if names.is_empty() {
+            // The same search as `load`, so only sources it would read are
+            // touched, and only a verified secret is cached.
+            match self.search_chain(&config)? {
+                Some((resolution, secret)) => {
+                    if let Some(expected) = &config.expected_sha256 {
+                        integrity::verify_sha256(&secret, expected)?;
+                    }
+                    if let Some(resolution) = resolution {
+                        self.remember(None, resolution, secret);
+                    }
+                }
+                None if config.optional => {}
+                None => return Err(KeyringError::NotFound(self.credential_name.clone())),
+            }
+        }
+        for (name, optional) in names {
+            match self.locate_name(&config, &name)? {
+                Some((resolution, secret)) => self.remember(Some(name), resolution, secret),
+                None if optional => {}
+                None => return Err(KeyringError::NotFound(name)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetch the secret again, bypassing the cache, and report whether it
+    /// changed since the provider last read it.
+    ///
+    /// Values are compared by SHA-256 digest, so the secret is never kept
+    /// or logged for the comparison. A cached secret is replaced by the
+    /// fresh one. A secret read for the first time counts as changed; one
+    /// that has disappeared does not, and fails unless the config sets
+    /// `optional`. Long-running services can call this periodically to pick
+    /// up rotated credentials and rebuild their figment when it returns
+    /// `true`.
+    ///
+    /// Only the provider's own credential is reloaded. Providers that read
+    /// several entries or sources, such as a credentials manifest,
+    /// [`merge_json_parts`](Self::merge_json_parts),
+    /// [`load_prefix`](Self::load_prefix),
+    /// [`expand_profiles`](Self::expand_profiles) or
+    /// [`fallback_chain`](Self::fallback_chain), fail with
+    /// [`KeyringError::Unsupported`].
+    pub fn reload(&self) -> error::Result<bool> {
+        let config = self.extract_config()?;
+        if self.reads_many(&config) || !self.fallback_chain.is_empty() {
+            return Err(KeyringError::Unsupported(
+                "reload of multiple credentials or sources".into(),
+            ));
+        }
+        match self.search_uncached(&config)? {
+            Some((resolution, secret)) => {
+                let changed = self.see(&secret);
+                let cached = self
+                    .cache
+                    .lock()
+                    .expect("secret cache poisoned")
+                    .contains_key(&None);
+                if cached || self.cache_ttl.is_some() {
+                    self.remember(None, resolution, secret);
+                }
+                Ok(changed)
+            }
+            None => {
+                self.cache
+                    .lock()
+                    .expect("secret cache poisoned")
+                    .remove(&None);
+                if config.optional {
+                    Ok(false)
+                } else {
+                    Err(KeyringError::NotFound(self.credential_name.clone()))
+                }
+            }
+        }
+    }
+
+    /// Stop trying a keyring for `cooldown` after `failure_threshold`
+    /// consecutive failures.
+    ///
+    /// While the circuit is open, lookups in that keyring fail immediately
+    /// with [`KeyringError::CircuitOpen`] and the search moves on to the next
+    /// keyring. After the cooldown the keyring is tried again. A success or a
+    /// missing secret resets the count. The failure counts are shared by
+    /// clones of the provider.
+    pub fn with_circuit_breaker(mut self, failure_threshold: u32, cooldown: Duration) -> Self {
+        self.breaker = Some(Arc::new(CircuitBreaker {
+            failure_threshold,
+            cooldown,
+            states: Mutex::new(HashMap::new()),
+        }));
+        self
+    }
+
+    /// Give up on a keyring lookup that takes longer than `timeout`.
+    ///
+    /// Reads run on a background thread shared by clones of the provider; a
+    /// lookup that overruns fails with [`KeyringError::Timeout`] and is
+    /// retried once before the error is handled like any other backend
+    /// error. The overrunning read cannot be interrupted: it keeps running,
+    /// and holds its thread, until the keyring returns. Later reads move to
+    /// a new thread rather than queue behind it.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Seed the random order of the `random` read strategy, so the order is
+    /// reproducible.
+    pub fn with_read_seed(mut self, seed: u64) -> Self {
+        self.read_rng = Arc::new(Mutex::new(seed));
+        self
+    }
+
+    /// Use `clock` instead of the system clock for cache and circuit breaker
+    /// expiry.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Remove a single trailing `\n` or `\r\n` from the secret.
+    ///
+    /// Tools that write secrets from the shell often append a newline. Only
+    /// that one line ending is removed; any other leading or trailing
+    /// whitespace is kept. The line ending is looked for in the text after
+    /// [`with_charset`](Self::with_charset) decoding.
+    pub fn strip_trailing_newline(mut self) -> Self {
+        self.strip_newline = true;
+        self
+    }
+
+    /// Remove a leading UTF-8 byte order mark from the secret.
+    ///
+    /// Some Windows tools prefix stored text with a BOM, which then breaks
+    /// exact comparisons. Nothing else is trimmed.
+    pub fn strip_bom(mut self) -> Self {
+        self.strip_bom = true;
+        self
+    }
+
+    /// Split the secret on `delimiter` and emit the parts as an array.
+    ///
+    /// Each part is trimmed and empty parts are dropped, so a secret of
+    /// `"a.example, b.example,,c.example\n"` split on `','` becomes
+    /// `["a.example", "b.example", "c.example"]`.
+    pub fn as_list(mut self, delimiter: char) -> Self {
+        self.list_delimiter = Some(delimiter);
+        self
+    }
+
+    /// Nest emitted keys on `separator` instead of `.`.
+    ///
+    /// By default a key such as `"database.password"` is emitted as
+    /// `{"database": {"password": ..}}`, matching figment's dotted paths.
+    /// Pass another separator such as `Some("/")` or `Some("::")` when
+    /// names legitimately contain dots, or `None` to emit keys verbatim.
+    ///
+    /// Only keys the provider names itself are nested: the credential or
+    /// [`as_key`](Self::as_key) key, its `_source` companion, and the key
+    /// of each [`EntrySpec`]. Keys that come from elsewhere,
+    /// such as JSON part fields, dotenv entries, manifest names and
+    /// [`load_prefix`](Self::load_prefix) usernames, are emitted as they
+    /// are.
+    pub fn with_key_separator(mut self, separator: Option<&str>) -> Self {
+        self.key_separator = separator.filter(|s| !s.is_empty()).map(Into::into);
+        self
+    }
+
+    /// Emit the secret as a one-element array under `key`.
+    ///
+    /// figment's `merge` and `join` replace an existing array wholesale, so
+    /// merge the provider with [`Figment::admerge`] (or `adjoin`) to append
+    /// the secret to an array such as `allowed_tokens` from earlier sources:
+    ///
+    /// ```rust,no_run
+    /// # use figment2::{Figment, providers::Serialized};
+    /// # use figment_keyring::KeyringProvider;
+    /// let figment = Figment::from(Serialized::default("allowed_tokens", vec!["public"]))
+    ///     .admerge(KeyringProvider::new("myapp", "admin_token").append_to_array("allowed_tokens"));
+    /// ```
+    pub fn append_to_array(mut self, key: &str) -> Self {
+        self.append_key = Some(key.into());
+        self
+    }
+
+    /// Record whether the provider's values should win over the values
+    /// already in a figment, as applied by [`into_merge`](Self::into_merge)
+    /// and [`into_admerge`](Self::into_admerge).
+    ///
+    /// The hint has no effect when the provider is passed to a figment
+    /// combinator directly. There, [`Figment::merge`] and
+    /// [`Figment::admerge`] let the provider's values win, while
+    /// [`Figment::join`] and [`Figment::adjoin`] keep the existing ones.
+    pub fn precedence_hint(mut self, precedence: Precedence) -> Self {
+        self.precedence = precedence;
+        self
+    }
+
+    /// Add the provider to `figment` with [`Figment::merge`], or
+    /// [`Figment::join`] under [`Precedence::Fallback`].
+    ///
+    /// Conflicting arrays are replaced wholesale by the winning side.
+    ///
+    /// ```rust,no_run
+    /// # use figment2::{Figment, providers::Env};
+    /// # use figment_keyring::{KeyringProvider, Precedence};
+    /// let figment = KeyringProvider::new("myapp", "api_key")
+    ///     .precedence_hint(Precedence::Fallback)
+    ///     .into_merge(Figment::from(Env::prefixed("MYAPP_")));
+    /// ```
+    pub fn into_merge(self, figment: Figment) -> Figment {
+        match self.precedence {
+            Precedence::Override => figment.merge(self),
+            Precedence::Fallback => figment.join(self),
+        }
+    }
+
+    /// Add the provider to `figment` with [`Figment::admerge`], or
+    /// [`Figment::adjoin`] under [`Precedence::Fallback`].
+    ///
+    /// Unlike [`into_merge`](Self::into_merge), conflicting arrays are
+    /// concatenated, so a secret emitted by
+    /// [`append_to_array`](Self::append_to_array) joins the existing array.
+    pub fn into_admerge(self, figment: Figment) -> Figment {
+        match self.precedence {
+            Precedence::Override => figment.admerge(self),
+            Precedence::Fallback => figment.adjoin(self),
+        }
+    }
+
+    /// Read the credential version named by a pointer entry.
+    ///
+    /// The entry `{credential}.{pointer_suffix}` holds the current version,
+    /// and the secret is then read from `{credential}.{version}`. With
+    /// `versioned("current")`, a pointer `api_key.current` holding `v4` selects
+    /// `api_key.v4`. Without a pointer entry the bare credential name is used.
+    /// The secret is emitted under the bare name either way.
+    pub fn versioned(mut self, pointer_suffix: &str) -> Self {
+        self.version_pointer = Some(pointer_suffix.into());
+        self
+    }
+
+    /// Assemble the output from several entries that each hold a JSON object.
+    ///
+    /// Every credential in `parts` is looked up, parsed as a JSON object and
+    /// deep-merged into the profile dict in list order: nested objects are
+    /// merged key by key and, on conflicting keys, the later part wins. The
+    /// provider's own credential name is not looked up.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::KeyringProvider;
+    /// let provider = KeyringProvider::new("myapp", "cfg")
+    ///     .merge_json_parts(vec!["cfg.part1", "cfg.part2"]);
+    /// ```
+    pub fn merge_json_parts(mut self, parts: Vec<&str>) -> Self {
+        self.json_parts = parts.into_iter().map(String::from).collect();
+        self
+    }
+
+    /// Read the secret as a JSON object mapping tenants to secrets and emit
+    /// each tenant as its own profile.
+    ///
+    /// A secret `{"acme": "s1", "globex": "s2"}` yields the profile `acme`
+    /// holding `{key: "s1"}` and `globex` holding `{key: "s2"}`, so the
+    /// application picks a tenant with [`Figment::select`]. Tenant values
+    /// are emitted as they appear in the JSON. The secret itself is looked
+    /// up, checked against `expected_sha256`, mirrored and consumed like any
+    /// single secret.
+    ///
+    /// ```rust,no_run
+    /// # use figment2::Figment;
+    /// # use figment_keyring::KeyringProvider;
+    /// let figment = Figment::new()
+    ///     .merge(KeyringProvider::new("myapp", "tenant_keys").as_tenant_profiles("api_key"))
+    ///     .select("acme");
+    /// ```
+    pub fn as_tenant_profiles(mut self, key: &str) -> Self {
+        self.tenant_key = Some(key.into());
+        self
+    }
+
+    /// Allow [`export_snapshot`](Self::export_snapshot) to materialize secrets.
+    pub fn allow_snapshot_export(mut self) -> Self {
+        self.snapshot_export = true;
+        self
+    }
+
+    /// Resolve the secrets this provider emits into a plain dict keyed by
+    /// profile name.
+    ///
+    /// The result contains the secret values in plain text, so exporting must
+    /// be enabled with [`allow_snapshot_export`](Self::allow_snapshot_export).
+    /// Feed the snapshot to [`from_snapshot`](Self::from_snapshot) to serve it
+    /// elsewhere without keyring access. Exporting neither consumes a
+    /// [`consume_once`](Self::consume_once) secret nor mirrors it.
+    pub fn export_snapshot(&self) -> error::Result<Dict> {
+        if !self.snapshot_export {
+            return Err(KeyringError::ConfigError(
+                "snapshot export is not enabled; call allow_snapshot_export()".into(),
+            ));
+        }
+
+        let data = self.without_side_effects().load_chained()?;
+        Ok(data
+            .into_iter()
+            .map(|(profile, dict)| (profile.to_string(), Value::from(dict)))
+            .collect())
+    }
+
+    /// Resolve the provider's data like [`Provider::data`], with every value
+    /// replaced by the string `"[redacted]"`.
+    ///
+    /// The profiles, keys and nesting are kept, so the result shows what the
+    /// provider contributes without revealing any secret. The secrets are
+    /// read to learn their shape, but a
+    /// [`consume_once`](Self::consume_once) secret is left in place and
+    /// nothing is mirrored.
+    pub fn data_shape(&self) -> error::Result<Map<Profile, Dict>> {
+        let data = self.without_side_effects().load_chained()?;
+        Ok(data
+            .into_iter()
+            .map(|(profile, dict)| (profile, redact_dict(dict)))
+            .collect())
+    }
+
+    /// Use a custom factory to create keyring entries.
+    ///
+    /// The factory replaces the default [`create_entry`](keyring_config::backend::create_entry)
+    /// for this provider, giving full control over how each
+    /// [`Entry`](keyring_core::Entry) is built (custom credential stores,
+    /// session options, ...). It is called with the keyring, service and
+    /// username of every lookup, along with the extra modifiers requested by
+    /// the config.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::Arc;
+    /// # use figment_keyring::{KeyringError, KeyringProvider};
+    /// let provider = KeyringProvider::new("myapp", "api_key").with_entry_factory(Arc::new(
+    ///     |_keyring, service, username, _modifiers| {
+    ///         keyring_core::Entry::new(service, username)
+    ///             .map_err(|e| KeyringError::BackendError(e.to_string()))
+    ///     },
+    /// ));
+    /// ```
+    pub fn with_entry_factory(mut self, factory: EntryFactory) -> Self {
+        self.entry_factory = Some(factory);
+        self
+    }
+
+    /// Use a custom function to list the entries stored for a service.
+    ///
+    /// This replaces the default [`search_entries`](keyring_config::backend::search_entries)
+    /// for features that enumerate credentials, and is usually paired with
+    /// [`with_entry_factory`](Self::with_entry_factory).
+    pub fn with_entry_search(mut self, search: EntrySearch) -> Self {
+        self.entry_search = Some(search);
+        self
+    }
+
+    /// Derive the profile from a suffix on the credential name.
+    ///
+    /// Entries named `{credential}{delimiter}{profile}` are enumerated from
+    /// each keyring and their values are placed under the base key in the
+    /// matching profile. An entry named exactly `{credential}` goes to the
+    /// provider's profile. With `split_profile_on('@')`, `api_key@production`
+    /// and `api_key@staging` become `api_key` in the `production` and
+    /// `staging` profiles.
+    pub fn split_profile_on(mut self, delimiter: char) -> Self {
+        self.profile_delimiter = Some(delimiter);
+        self
+    }
+
+    /// Look up one credential per profile suffix and emit each in its
+    /// profile.
+    ///
+    /// For every suffix the credential `{credential}.{suffix}` is fetched and
+    /// placed under the base key in the profile named by the suffix, so
+    /// `expand_profiles(vec!["staging", "production"])` on `api_key` reads
+    /// `api_key.staging` and `api_key.production`. Unlike
+    /// [`split_profile_on`](Self::split_profile_on) no keyring is
+    /// enumerated; when both are set, its delimiter replaces the `.`. A
+    /// missing suffix fails with [`KeyringError::NotFound`] unless the config
+    /// is `optional`.
+    pub fn expand_profiles(mut self, suffixes: Vec<&str>) -> Self {
+        self.profile_suffixes = suffixes.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl KeyringProvider {
+    /// Store a secret in the first configured keyring whose role allows
+    /// writes.
+    ///
+    /// Any existing value for the credential in that keyring is replaced.
+    /// Fails with [`KeyringError::PermissionDenied`] when the config sets
+    /// `read_only` or every keyring has the [`Read`](KeyringRole::Read)
+    /// role.
+    pub fn store(&self, value: &str) -> error::Result<()> {
+        let entry = self.write_entry()?;
+        self.clear_cache();
+        keyring_config::backend::write_secret(&entry, value)
+    }
+
+    /// Store a secret in the first writable keyring along with attributes
+    /// such as `created_by` or `rotated_at`.
+    ///
+    /// The secret is written first and the attributes are then set on its
+    /// credential; they can be read back with
+    /// [`attributes`](Self::attributes). Stores that cannot hold attributes
+    /// fail with [`KeyringError::Unsupported`] after the secret is written.
+    pub fn store_with_attributes(
+        &self,
+        value: &str,
+        attributes: HashMap<String, String>,
+    ) -> error::Result<()> {
+        let entry = self.write_entry()?;
+        self.clear_cache();
+        keyring_config::backend::write_secret(&entry, value)?;
+        if attributes.is_empty() {
+            return Ok(());
+        }
+        keyring_config::backend::write_attributes(&entry, &attributes)
+    }
+
+    /// Delete the secret from the first writable keyring.
+    ///
+    /// Fails with [`KeyringError::PermissionDenied`] when the config sets
+    /// `read_only` or every keyring has the [`Read`](KeyringRole::Read)
+    /// role.
+    pub fn delete(&self) -> error::Result<()> {
+        let entry = self.write_entry()?;
+        self.clear_cache();
+        keyring_config::backend::delete_secret(&entry)
+    }
+
+    /// Look up the secret and report where it was found, without emitting it.
+    ///
+    /// Returns `None` when the secret is not present in any keyring.
+    pub fn resolve(&self) -> error::Result<Option<Resolution>> {
+        let config = self.extract_config()?;
+        Ok(self
+            .search_keyrings(&config)?
+            .map(|(resolution, _)| resolution))
+    }
+
+    /// Run the lookup like [`resolve`](Self::resolve) and time every keyring
+    /// access.
+    ///
+    /// Each attempt is reported in search order with its duration and
+    /// outcome. The search stops where [`Provider::data`] would: at the first
+    /// keyring holding the secret or at an error that is not skipped.
+    pub fn diagnose(&self) -> error::Result<Vec<(Keyring, Duration, error::Result<()>)>> {
+        let config = self.extract_config()?;
+        let mut timings = Vec::new();
+        for lookup in self.versioned_lookups(&config)? {
+            let start = Instant::now();
+            let result = self.get_from_keyring(&config, &lookup.keyring, &lookup.credential);
+            let elapsed = start.elapsed();
+
+            let stop = match &result {
+                Ok(_) => true,
+                Err(e) => !skippable(&config, &lookup.keyring, e),
+            };
+            timings.push((lookup.keyring, elapsed, result.map(|_| ())));
+            if stop {
+                break;
+            }
+        }
+        Ok(timings)
+    }
+
+    /// Report which figment source supplied each keyring config key.
+    ///
+    /// Because the provider is configured through late binding, it is not
+    /// always obvious which source won. Each entry pairs a [`KeyringConfig`]
+    /// key with the name (and source, when known) of the figment provider
+    /// that supplied it, or `"default"` when the key was not set and the
+    /// built-in default applies.
+    pub fn config_provenance(&self) -> error::Result<Vec<(String, String)>> {
+        let keys = Value::serialize(KeyringConfig::default())
+            .ok()
+            .and_then(|value| value.into_dict())
+            .ok_or_else(|| KeyringError::ConfigError("config keys unavailable".into()))?;
+
+        Ok(keys
+            .into_keys()
+            .map(|key| {
+                let source = match self.config_figment.find_metadata(&key) {
+                    Some(metadata) => match &metadata.source {
+                        Some(source) => format!("{} ({})", metadata.name, source),
+                        None => metadata.name.to_string(),
+                    },
+                    None => "default".to_string(),
+                };
+                (key, source)
+            })
+            .collect())
+    }
+
+    /// Look up the secret and return it as a string, bypassing figment.
+    ///
+    /// The keyrings are searched exactly as in [`Provider::data`]. A missing
+    /// secret is `Ok(None)` when the config sets `optional` and
+    /// [`KeyringError::NotFound`] otherwise. Secrets that are not valid UTF-8
+    /// are converted lossily only when `on_invalid_utf8` is `lossy`.
+    pub fn secret(&self) -> error::Result<Option<String>> {
+        let config = self.extract_config()?;
+        let Some(secret) = self.lookup_secret(&config)? else {
+            return Ok(None);
+        };
+        secret_string(&config, secret, &self.credential_name).map(Some)
+    }
+
+    /// Defer the lookup until the secret is first asked for, bypassing
+    /// figment.
+    ///
+    /// figment calls [`Provider::data`] when the provider is merged, not when
+    /// a key is extracted, so a merged provider cannot be lazy. Keep the
+    /// returned [`LazySecret`] alongside the config instead and call
+    /// [`LazySecret::get`] where the secret is needed; secrets that are never
+    /// used are never fetched.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::KeyringProvider;
+    /// let smtp_password = KeyringProvider::new("myapp", "smtp_password").lazy();
+    /// // ... later, only if mail is sent:
+    /// let password = smtp_password.get()?;
+    /// # Ok::<(), figment_keyring::error::KeyringError>(())
+    /// ```
+    pub fn lazy(self) -> LazySecret {
+        LazySecret::new(self)
+    }
+
+    /// Look up the secret and hand it to `setter`, bypassing figment.
+    ///
+    /// For config structs that are filled in code rather than extracted.
+    /// Returns whether `setter` was called; it is not called when an
+    /// `optional` secret is missing. Errors are those of
+    /// [`secret`](Self::secret).
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::KeyringProvider;
+    /// # #[derive(Default)]
+    /// # struct Config { api_key: String }
+    /// let mut config = Config::default();
+    /// KeyringProvider::new("myapp", "api_key").apply_to(|secret| config.api_key = secret)?;
+    /// # Ok::<(), figment_keyring::error::KeyringError>(())
+    /// ```
+    pub fn apply_to(&self, mut setter: impl FnMut(String)) -> error::Result<bool> {
+        match self.secret()? {
+            Some(secret) => {
+                setter(secret);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Look up the secret and return its raw bytes, bypassing figment.
+    ///
+    /// See [`secret`](Self::secret) for how a missing secret is reported.
+    pub fn secret_bytes(&self) -> error::Result<Option<Vec<u8>>> {
+        let config = self.extract_config()?;
+        self.lookup_secret(&config)
+    }
+
+    /// Replace `keyring:<name>` references in `figment` with their secrets.
+    ///
+    /// Every string value, in every profile and at any depth of nested
+    /// tables and arrays, that starts with `keyring:` is looked up as the
+    /// credential `<name>` in the configured keyrings. Other values,
+    /// including keys, are left untouched. A reference that is not found in
+    /// any keyring fails with [`KeyringError::NotFound`] even when the config
+    /// sets `optional`, so a literal reference is never handed out as a
+    /// secret.
+    ///
+    /// ```rust,no_run
+    /// # use figment2::{Figment, providers::Serialized};
+    /// # use figment_keyring::KeyringProvider;
+    /// let figment = Figment::from(Serialized::default("database.password", "keyring:db_password"));
+    /// let figment = KeyringProvider::new("myapp", "api_key").resolve_references(figment)?;
+    /// # Ok::<(), figment_keyring::error::KeyringError>(())
+    /// ```
+    pub fn resolve_references(&self, figment: Figment) -> error::Result<Figment> {
+        let config = self.extract_config()?;
+        let data = figment
+            .data()
+            .map_err(|e| KeyringError::ConfigError(e.to_string()))?;
+
+        let mut resolved = Figment::new().select(figment.profile().clone());
+        for (profile, dict) in data {
+            let mut out = Dict::new();
+            for (key, value) in dict {
+                out.insert(key, self.resolve_value(&config, value)?);
+            }
+            resolved = resolved.merge(Serialized::from(out, profile));
+        }
+        Ok(resolved)
+    }
+
+    fn resolve_value(
+        &self,
+        config: &KeyringConfig,
+        value: Value,
+    ) -> std::result::Result<Value, KeyringError> {
+        Ok(match value {
+            Value::String(_, s) if s.starts_with(REFERENCE_PREFIX) => {
+                let name = &s[REFERENCE_PREFIX.len()..];
+                let secret = self
+                    .search_name(config, name)?
+                    .ok_or_else(|| KeyringError::NotFound(name.to_string()))?;
+                let secret = self.prepare(secret, name)?;
+                Value::from(secret_string(config, secret, name)?)
+            }
+            Value::Dict(tag, dict) => {
+                let mut out = Dict::new();
+                for (key, value) in dict {
+                    out.insert(key, self.resolve_value(config, value)?);
+                }
+                Value::Dict(tag, out)
+            }
+            Value::Array(tag, values) => Value::Array(
+                tag,
+                values
+                    .into_iter()
+                    .map(|value| self.resolve_value(config, value))
+                    .collect::<std::result::Result<_, _>>()?,
+            ),
+            value => value,
+        })
+    }
+
+    fn lookup_secret(&self, config: &KeyringConfig) -> error::Result<Option<Vec<u8>>> {
+        match self.search_chain(config)? {
+            Some((resolution, secret)) => {
+                if let Some(expected) = &config.expected_sha256 {
+                    integrity::verify_sha256(&secret, expected)?;
+                }
+                if let Some(resolution) = &resolution {
+                    self.mirror(config, resolution, &secret);
+                }
+                let secret = self.prepare(secret, &self.credential_name)?;
+                if let Some(resolution) = &resolution {
+                    self.consume(config, resolution);
+                }
+                Ok(Some(secret))
+            }
+            None if config.optional => Ok(None),
+            None => Err(KeyringError::NotFound(self.credential_name.clone())),
+        }
+    }
+
+    /// Describe the secrets the provider needs, without reading any.
+    ///
+    /// The config is resolved as for [`Provider::data`], so the descriptor
+    /// reflects the keyrings and options in effect.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::KeyringProvider;
+    /// let descriptor = KeyringProvider::new("myapp", "api_key").descriptor()?;
+    /// println!("{}", serde_json::to_string_pretty(&descriptor).unwrap());
+    /// # Ok::<(), figment_keyring::error::KeyringError>(())
+    /// ```
+    pub fn descriptor(&self) -> error::Result<ProviderDescriptor> {
+        let config = self.extract_config()?;
+        Ok(ProviderDescriptor {
+            keyrings: config.search_order(),
+            credential: self.credential_name.clone(),
+            config_key: self.append_key.clone().or_else(|| self.config_key.clone()),
+            credentials: config.credentials,
+            json_parts: self.json_parts.clone(),
+            prefix: self.prefix.clone(),
+            profile: self.target_profile().to_string(),
+            optional: config.optional,
+            read_only: config.read_only,
+            encoding: self.encoding,
+            service: config.service,
+        })
+    }
+
+    /// Check that every configured keyring can be reached.
+    ///
+    /// Each keyring in search order is asked for a credential name that is
+    /// never stored, so no real secret is read. A keyring that answers, even
+    /// with "not found", is reachable; any other error marks it unreachable.
+    pub fn self_test(&self) -> error::Result<SelfTestReport> {
+        let config = self.extract_config()?;
+        let keyrings = config
+            .search_order()
+            .into_iter()
+            .map(|keyring| {
+                let error = match self.get_from_keyring(&config, &keyring, SELF_TEST_PROBE) {
+                    Ok(_) | Err(KeyringError::NotFound(_)) => None,
+                    Err(e) => Some(e),
+                };
+                KeyringStatus { keyring, error }
+            })
+            .collect();
+        Ok(SelfTestReport { keyrings })
+    }
+
+    /// List every credential stored under the service, with the keyrings
+    /// that hold it.
+    ///
+    /// Each configured keyring is enumerated in search order and the names
+    /// are merged, sorted by name, with the keyrings of each in search
+    /// order. No secret is read. A keyring whose store cannot enumerate
+    /// entries contributes nothing; other errors follow the config's
+    /// `optional` and `primary_strict` policy.
+    pub fn inventory(&self) -> error::Result<Vec<(String, Vec<Keyring>)>> {
+        let config = self.extract_config()?;
+        let mut found: BTreeMap<String, Vec<Keyring>> = BTreeMap::new();
+        for keyring in &config.search_order() {
+            let entries = match self.list_entries(&config, keyring) {
+                Ok(entries) => entries,
+                Err(KeyringError::Unsupported(_)) => continue,
+                Err(_) if config.tolerates_errors(keyring) => continue,
+                Err(e) => return Err(e),
+            };
+            for entry in entries {
+                let Some((service, username)) = entry.get_specifiers() else {
+                    continue;
+                };
+                if service != config.service {
+                    continue;
+                }
+                let keyrings = found.entry(username).or_default();
+                if !keyrings.contains(keyring) {
+                    keyrings.push(keyring.clone());
+                }
+            }
+        }
+        Ok(found.into_iter().collect())
+    }
+
+    /// Load every credential in the config's `credentials` manifest,
+    /// collecting failures instead of stopping at the first one.
+    ///
+    /// Returns the dict of credentials that resolved, keyed by name, and the
+    /// name and error of each one that did not, so an application can start
+    /// degraded and report what is missing. Missing credentials are only
+    /// failures when the config is not `optional`. A config that cannot be
+    /// extracted is reported as a failure named `keyring config`.
+    pub fn load_all_reporting(&self) -> (Dict, Vec<(String, KeyringError)>) {
+        match self.extract_config() {
+            Ok(config) => self.load_manifest(&config),
+            Err(e) => (Dict::new(), vec![("keyring config".into(), e)]),
+        }
+    }
+
+    /// Read the attributes stored with the secret, from the first keyring
+    /// that holds it.
+    ///
+    /// Returns `None` when the secret is not present in any keyring.
+    pub fn attributes(&self) -> error::Result<Option<HashMap<String, String>>> {
+        let config = self.extract_config()?;
+        for lookup in self.versioned_lookups(&config)? {
+            let entry = self.entry(&config, &lookup.keyring, &lookup.credential)?;
+            match keyring_config::backend::read_attributes(&entry) {
+                Ok(attributes) => return Ok(Some(attributes)),
+                Err(KeyringError::NotFound(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether the secret is present in any configured keyring.
+    pub fn exists(&self) -> error::Result<bool> {
+        let config = self.extract_config()?;
+        for lookup in self.versioned_lookups(&config)? {
+            match self.get_from_keyring(&config, &lookup.keyring, &lookup.credential) {
+                Ok(_) => return Ok(true),
+                Err(KeyringError::NotFound(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(false)
+    }
+
+    /// Store a secret only if it is not already present in any configured
+    /// keyring, returning whether it was written.
+    ///
+    /// This makes first-run bootstrapping idempotent without overwriting a
+    /// value the user has already set.
+    pub fn set_if_absent(&self, value: &str) -> error::Result<bool> {
+        if self.exists()? {
+            return Ok(false);
+        }
+        self.store(value)?;
+        Ok(true)
+    }
+
+    /// Copy the secret from the `from` keyring into the `to` keyring,
+    /// returning whether it was copied.
+    ///
+    /// Nothing is copied when `from` holds no secret or `to` already holds
+    /// one, so the call is safe to repeat. The secret is left in `from`.
+    /// Errors name the keyring that failed.
+    pub fn migrate(&self, from: Keyring, to: Keyring) -> error::Result<bool> {
+        let config = self.extract_config()?;
+        config.check_writable(&to)?;
+
+        let secret = match self.get_from_keyring(&config, &from, self.username_for(&from)) {
+            Ok(secret) => secret,
+            Err(KeyringError::NotFound(_)) => return Ok(false),
+            Err(e) => return Err(e.context(&format!("reading from {} keyring", from.as_str()))),
+        };
+        let target = format!("writing to {} keyring", to.as_str());
+        let entry = self
+            .entry(&config, &to, self.username_for(&to))
+            .map_err(|e| e.context(&target))?;
+        match keyring_config::backend::read_secret_bytes(&entry) {
+            Ok(_) => return Ok(false),
+            Err(KeyringError::NotFound(_)) => {}
+            Err(e) => return Err(e.context(&target)),
+        }
+        keyring_config::backend::write_secret_bytes(&entry, &secret)
+            .map_err(|e| e.context(&target))?;
+        self.clear_cache();
+        Ok(true)
+    }
+
+    /// Generate a random alphanumeric secret of `len` characters, store it
+    /// in the first configured keyring and return it.
+    ///
+    /// The secret is drawn from the thread-local CSPRNG of the `rand` crate,
+    /// or from the generator set with [`with_rng`](Self::with_rng). Like
+    /// [`store`](Self::store), this replaces any existing value.
+    #[cfg(feature = "generate")]
+    pub fn generate_and_store(&self, len: usize) -> error::Result<String> {
+        let secret: String = match &self.rng {
+            Some(rng) => {
+                let mut rng = rng.lock().expect("rng poisoned");
+                alphanumeric(&mut *rng, len)
+            }
+            None => alphanumeric(&mut rand::rng(), len),
+        };
+        self.store(&secret)?;
+        Ok(secret)
+    }
+
+    /// Draw secrets for [`generate_and_store`](Self::generate_and_store)
+    /// from `rng` instead of the thread-local CSPRNG.
+    ///
+    /// Meant for tests, which can pass a seeded generator to get a known
+    /// secret. The generator is shared by clones of the provider.
+    ///
+    /// ```rust,no_run
+    /// # use figment_keyring::KeyringProvider;
+    /// use rand::{SeedableRng, rngs::StdRng};
+    ///
+    /// let provider = KeyringProvider::new("myapp", "session_key")
+    ///     .with_rng(StdRng::seed_from_u64(42));
+    /// ```
+    #[cfg(feature = "generate")]
+    pub fn with_rng(mut self, rng: impl rand::Rng + Send + 'static) -> Self {
+        self.rng = Some(Arc::new(Mutex::new(rng)));
+        self
+    }
+}
+
+impl Provider for KeyringProvider {
+    fn metadata(&self) -> Metadata {
+        match self.snapshot {
+            Some(_) => Metadata::from("keyring snapshot", sensitive_source()),
+            None => Metadata::from("keyring", sensitive_source()),
+        }
+    }
+
+    fn data(&self) -> std::result::Result<Map<Profile, Dict>, Error> {
+        match (self.load_chained(), &self.error_mapper) {
+            (Ok(map), _) => Ok(map),
+            (Err(e), Some(mapper)) => {
+                let missing = matches!(e, KeyringError::NotFound(_));
+                let mapped = mapper(e).map_err(|e| if missing { self.with_help(e) } else { e });
+                let mut dict = Dict::new();
+                if let Some(value) = mapped? {
+                    dict.insert(self.emitted_key().clone(), value);
+                    self.nest_keys(&mut dict, std::slice::from_ref(self.emitted_key()));
+                }
+                let map = Map::from([(self.target_profile(), dict)]);
+                match self.extract_config() {
+                    Ok(config) => Ok(self.finish(&config, map)),
+                    // Namespacing needs the service from the config.
+                    Err(e) if self.namespaced => Err(figment_error(e)),
+                    Err(_) => Ok(self.finish(&KeyringConfig::default(), map)),
+                }
+            }
+            (Err(e @ KeyringError::NotFound(_)), None) => Err(self.with_help(figment_error(e))),
+            (Err(e), None) => Err(figment_error(e)),
+        }
+    }
+}
+
+impl KeyringProvider {
+    /// Resolve the provider's data using an asynchronous backend.
+    ///
+    /// Like [`Provider::data`], it searches the configured keyrings in order
+    /// with the same error policy, but fetches every secret through
+    /// `backend` instead of keyring-core entries. It lets remote or otherwise
+    /// async secret stores sit behind the same configuration.
+    ///
+    /// Enumerating and multi-entry features such as
+    /// [`split_profile_on`](Self::split_profile_on) and
+    /// [`merge_json_parts`](Self::merge_json_parts) are not supported, and
+    /// neither is an [`or`](Self::or) fallback; these fail with a
+    /// `ConfigError`. Side effects of a read are skipped: the secret is not
+    /// copied by [`mirror_to`](Self::mirror_to) nor deleted by
+    /// [`consume_once`](Self::consume_once), since `backend` has no write path.
+    pub async fn data_async<B: AsyncSecretBackend>(
+        &self,
+        backend: &B,
+    ) -> error::Result<Map<Profile, Dict>> {
+        if let Some(snapshot) = &self.snapshot {
+            return snapshot_data(snapshot);
+        }
+        if !self.profile_suffixes.is_empty() {
+            return Err(KeyringError::ConfigError(
+                "expand_profiles is not supported by data_async".into(),
+            ));
+        }
+        if self.profile_delimiter.is_some() {
+            return Err(KeyringError::ConfigError(
+                "split_profile_on is not supported by data_async".into(),
+            ));
+        }
+        if !self.json_parts.is_empty() {
+            return Err(KeyringError::ConfigError(
+                "merge_json_parts is not supported by data_async".into(),
+            ));
+        }
+        if self.tenant_key.is_some() {
+            return Err(KeyringError::ConfigError(
+                "as_tenant_profiles is not supported by data_async".into(),
+            ));
+        }
+        if self.or_else.is_some() {
+            return Err(KeyringError::ConfigError(
+                "or is not supported by data_async".into(),
+            ));
+        }
+        if self.prefix.is_some() {
+            return Err(KeyringError::ConfigError(
+                "load_prefix is not supported by data_async".into(),
+            ));
+        }
+
+        if !self.enabled()? {
+            return Ok(self.disabled());
+        }
+
+        let config = self.extract_config()?;
+        if !config.credentials.is_empty() {
+            return Err(KeyringError::ConfigError(
+                "a credentials manifest is not supported by data_async".into(),
+            ));
+        }
+        if !config.secrets.is_empty() {
+            return Err(KeyringError::ConfigError(
+                "a secrets list is not supported by data_async".into(),
+            ));
+        }
+
+        let mut secret = None;
+        for resolution in self.lookups(&config) {
+            match backend
+                .get(&resolution.keyring, &config.service, &resolution.credential)
+                .await
+            {
+                Ok(value) => {
+                    let value = value.into_bytes();
+                    if let Some(expected) = &config.expected_sha256 {
+                        integrity::verify_sha256(&value, expected)?;
+                    }
+                    secret = Some((resolution, value));
+                    break;
+                }
+                Err(e) if skippable(&config, &resolution.keyring, &e) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let source = secret.as_ref().map(|(resolution, _)| resolution.clone());
+        let secret = secret.map(|(_, value)| value);
+        self.output(&config, self.in_profile(secret), source.as_ref())
+    }
+}
+
+#[cfg(feature = "cancellation")]
+impl KeyringProvider {
+    /// [`data_async`](Self::data_async), abandoned when `token` is cancelled.
+    ///
+    /// On cancellation the in-flight lookup is dropped instead of awaited and
+    /// the call fails with `KeyringError::ServiceUnavailable("cancelled")`,
+    /// so an aborted startup does not hang on a slow backend.
+    pub async fn data_async_cancellable<B: AsyncSecretBackend>(
+        &self,
+        backend: &B,
+        token: &tokio_util::sync::CancellationToken,
+    ) -> error::Result<Map<Profile, Dict>> {
+        token
+            .run_until_cancelled(self.data_async(backend))
+            .await
+            .unwrap_or_else(|| Err(KeyringError::ServiceUnavailable("cancelled".into())))
+    }
+}
+
+fn figment_error(e: KeyringError) -> Error {
+    match e {
+        KeyringError::NotFound(name) => {
+            Error::from(format!("secret '{}' not found in any keyring", name))
+        }
+        e => Error::from(e.to_string()),
+    }
+}
+
+/// The metadata source marking values that came from a keyring.
+fn sensitive_source() -> Source {
+    Source::Custom("sensitive".into())
+}
+
+/// Scrub secret values out of an extraction error.
+///
+/// figment quotes the offending value when a field fails to deserialize,
+/// which can leak a secret into logs. Every error in the chain whose value
+/// originated from a [`KeyringProvider`] has that value replaced by a
+/// placeholder; errors from other providers are left untouched.
+///
+/// ```rust
+/// use figment2::Figment;
+/// use figment_keyring::redact_figment_error;
+///
+/// # fn extract(figment: Figment) -> Result<u32, figment2::Error> {
+/// figment.extract_inner::<u32>("api_key").map_err(redact_figment_error)
+/// # }
+/// ```
+pub fn redact_figment_error(error: Error) -> Error {
+    let mut errors: Vec<Error> = error.into_iter().map(redact_one).collect();
+    let mut chained = errors.pop().expect("an error chain is never empty");
+    while let Some(error) = errors.pop() {
+        chained = chained.chain(error);
+    }
+    chained
+}
+
+fn redact_one(mut error: Error) -> Error {
+    let sensitive = error
+        .metadata
+        .as_ref()
+        .is_some_and(|metadata| metadata.source == Some(sensitive_source()));
+    if !sensitive {
+        return error;
+    }
+
+    error.kind = match error.kind {
+        Kind::InvalidType(actual, expected) => Kind::InvalidType(redact_actual(actual), expected),
+        Kind::InvalidValue(actual, expected) => Kind::InvalidValue(redact_actual(actual), expected),
+        Kind::Unsupported(actual) => Kind::Unsupported(redact_actual(actual)),
+        Kind::UnsupportedKey(actual, expected) => {
+            Kind::UnsupportedKey(redact_actual(actual), expected)
+        }
+        Kind::UnknownVariant(_, expected) => Kind::UnknownVariant("[redacted]".into(), expected),
+        kind => kind,
+    };
+    error
+}
+
+fn redact_actual(actual: Actual) -> Actual {
+    match actual {
+        Actual::Bool(_)
+        | Actual::Unsigned(_)
+        | Actual::Signed(_)
+        | Actual::Float(_)
+        | Actual::Char(_)
+        | Actual::Str(_)
+        | Actual::Bytes(_)
+        | Actual::Other(_) => Actual::Other("[redacted] value".into()),
+        actual => actual,
+    }
+}
+
+fn redact_dict(dict: Dict) -> Dict {
+    dict.into_iter()
+        .map(|(key, value)| (key, redact_value(value)))
+        .collect()
+}
+
+fn redact_value(value: Value) -> Value {
+    match value {
+        Value::Dict(_, dict) => Value::from(redact_dict(dict)),
+        Value::Array(_, values) => {
+            Value::from(values.into_iter().map(redact_value).collect::<Vec<_>>())
+        }
+        _ => Value::from("[redacted]"),
+    }
+}
+
+fn secret_string(
+    config: &KeyringConfig,
+    secret: Vec<u8>,
+    name: &str,
+) -> std::result::Result<String, KeyringError> {
+    match String::from_utf8(secret) {
+        Ok(secret) => Ok(secret),
+        Err(e) if config.on_invalid_utf8 == InvalidUtf8::Lossy => {
+            Ok(String::from_utf8_lossy(e.as_bytes()).into_owned())
+        }
+        Err(e) => Err(KeyringError::ParseError(format!(
+            "secret '{}' is not valid UTF-8 (invalid byte at offset {})",
+            name,
+            e.utf8_error().valid_up_to()
+        ))),
+    }
+}
+
+fn snapshot_data(snapshot: &Dict) -> error::Result<Map<Profile, Dict>> {
+    let mut map = Map::new();
+    for (profile, value) in snapshot.iter() {
+        let Some(dict) = value.as_dict() else {
+            return Err(KeyringError::ConfigError(format!(
+                "keyring snapshot: profile '{}' is not a dict",
+                profile
+            )));
+        };
+        map.insert(Profile::from(profile.as_str()), dict.clone());
+    }
+    Ok(map)
+}
+
+/// A random alphanumeric string of `len` characters drawn from `rng`.
+#[cfg(feature = "generate")]
+fn alphanumeric<R: rand::Rng + ?Sized>(rng: &mut R, len: usize) -> String {
+    use rand::RngExt;
+
+    rng.sample_iter(rand::distr::Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// A seed for the `random` read strategy that differs between providers.
+fn random_seed() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish()
+}
+
+/// Advance the xorshift generator `state` and return its next value.
+fn next_random(state: &mut u64) -> u64 {
+    // Zero is a fixed point of xorshift.
+    let mut x = if *state == 0 {
+        0x9e37_79b9_7f4a_7c15
+    } else {
+        *state
+    };
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    *state = x;
+    x
+}
+
+/// Whether a lookup error lets the search move on to the next keyring.
+fn skippable(config: &KeyringConfig, keyring: &Keyring, error: &KeyringError) -> bool {
+    match error {
+        KeyringError::NotFound(_) | KeyringError::CircuitOpen(_) => true,
+        _ if config.is_strict(keyring) => false,
+        KeyringError::PermissionDenied(_) if config.continue_on_denied => true,
+        _ => config.tolerates_errors(keyring),
+    }
+}
+
+impl KeyringProvider {
+    /// Everything [`Provider::data`] does short of surfacing errors.
+    fn load(&self) -> std::result::Result<Map<Profile, Dict>, KeyringError> {
+        if let Some(snapshot) = &self.snapshot {
+            return snapshot_data(snapshot);
+        }
+
+        if !self.enabled()? {
+            return Ok(self.disabled());
+        }
+
+        let config = self.extract_config()?;
+        self.check_single_hash(&config)?;
+
+        if !self.profile_suffixes.is_empty() {
+            let found = self.search_suffixes(&config)?;
+            return self.output(&config, found, None);
+        }
+
+        if let Some(delimiter) = self.profile_delimiter {
+            let found = self.search_profiles(&config, delimiter)?;
+            return self.output(&config, found, None);
+        }
+
+        if !config.secrets.is_empty() {
+            let loaded = self.load_specs(&config)?;
+            let mut map = Map::new();
+            map.insert(self.target_profile(), loaded);
+            return Ok(self.finish(&config, map));
+        }
+
+        if !config.credentials.is_empty() {
+            let (loaded, failures) = self.load_manifest(&config);
+            if let Some((_, e)) = failures.into_iter().next() {
+                return Err(e);
+            }
+            let mut map = Map::new();
+            map.insert(self.target_profile(), loaded);
+            return Ok(self.finish(&config, map));
+        }
+
+        if !self.json_parts.is_empty() {
+            let merged = self.merge_parts(&config)?;
+            let mut map = Map::new();
+            map.insert(self.target_profile(), merged);
+            return Ok(self.finish(&config, map));
+        }
+
+        if let Some(key) = &self.tenant_key {
+            let tenants = self.search_tenants(&config, key)?;
+            return Ok(self.finish(&config, tenants));
+        }
+
+        if let Some(prefix) = &self.prefix {
+            let loaded = self.search_prefix(&config, prefix)?;
+            return Ok(self.finish(&config, loaded));
+        }
+
+        let secret = self.search_chain(&config)?;
+        if let Some((resolution, value)) = &secret {
+            if let Some(expected) = &config.expected_sha256 {
+                integrity::verify_sha256(value, expected)?;
+            }
+            if let Some(resolution) = resolution {
+                self.mirror(&config, resolution, value);
+            }
+        }
+        let found = secret
+            .as_ref()
+            .and_then(|(resolution, _)| resolution.clone());
+        let secret = secret.map(|(_, value)| value);
+        let output = self.output(&config, self.in_profile(secret), found.as_ref())?;
+        if let Some(resolution) = &found {
+            self.consume(&config, resolution);
+        }
+        Ok(output)
+    }
+
+    /// Whether the provider reads several credentials rather than its own
+    /// single one.
+    fn reads_many(&self, config: &KeyringConfig) -> bool {
+        !config.credentials.is_empty()
+            || !config.secrets.is_empty()
+            || !self.json_parts.is_empty()
+            || !self.profile_suffixes.is_empty()
+            || self.profile_delimiter.is_some()
+            || self.prefix.is_some()
+    }
+
+    /// `expected_sha256` pins one secret, so it is rejected where several
+    /// are read.
+    fn check_single_hash(&self, config: &KeyringConfig) -> std::result::Result<(), KeyringError> {
+        if config.expected_sha256.is_some() && self.reads_many(config) {
+            return Err(KeyringError::ConfigError(
+                "expected_sha256 only applies to a single credential".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// A copy of the provider whose reads leave no trace: secrets are
+    /// neither consumed nor mirrored, and the caches and circuit breaker
+    /// start from this provider's state without writing back to it.
+    fn without_side_effects(&self) -> Self {
+        let breaker = self.breaker.as_ref().map(|breaker| {
+            let states = breaker.states.lock().expect("circuit breaker poisoned");
+            Arc::new(CircuitBreaker {
+                failure_threshold: breaker.failure_threshold,
+                cooldown: breaker.cooldown,
+                states: Mutex::new(states.clone()),
+            })
+        });
+        Self {
+            consume: false,
+            mirror_target: None,
+            cache: Arc::new(Mutex::new(
+                self.cache.lock().expect("secret cache poisoned").clone(),
+            )),
+            misses: Arc::new(Mutex::new(
+                self.misses.lock().expect("miss cache poisoned").clone(),
+            )),
+            seen_digest: Arc::new(Mutex::new(
+                *self.seen_digest.lock().expect("seen digest poisoned"),
+            )),
+            read_counter: Arc::new(AtomicUsize::new(self.read_counter.load(Ordering::Relaxed))),
+            read_rng: Arc::new(Mutex::new(
+                *self.read_rng.lock().expect("read rng poisoned"),
+            )),
+            breaker,
+            or_else: self
+                .or_else
+                .as_ref()
+                .map(|other| Box::new(other.without_side_effects())),
+            ..self.clone()
+        }
+    }
+
+    /// [`load`](Self::load), falling back along the [`or`](Self::or) chain
+    /// when this provider supplies no value.
+    fn load_chained(&self) -> std::result::Result<Map<Profile, Dict>, KeyringError> {
+        let loaded = self.load();
+        let Some(other) = &self.or_else else {
+            return loaded;
+        };
+        // Switched off by `enabled_if` is not a miss.
+        let disabled = matches!(self.enabled(), Ok(false));
+        match &loaded {
+            Ok(map) if !disabled && map.values().all(Dict::is_empty) => other.load_chained(),
+            Err(KeyringError::NotFound(_)) => other.load_chained(),
+            _ => loaded,
+        }
+    }
+
+    /// Append the [`help`](Self::help) hint to `error`'s message.
+    fn with_help(&self, mut error: Error) -> Error {
+        let Some(hint) = &self.help else {
+            return error;
+        };
+        match &mut error.kind {
+            Kind::Message(message) => {
+                message.push_str(&format!(" (help: {})", hint));
+                error
+            }
+            _ => Error::from(format!("{} (help: {})", error, hint)),
+        }
+    }
+
+    /// Key the single secret is emitted under.
+    fn emitted_key(&self) -> &String {
+        self.append_key
+            .as_ref()
+            .or(self.config_key.as_ref())
+            .unwrap_or(&self.credential_name)
+    }
+
+    fn username_for(&self, keyring: &Keyring) -> &str {
+        self.credential_overrides
+            .get(keyring)
+            .unwrap_or(&self.credential_name)
+    }
+
+    fn enabled(&self) -> std::result::Result<bool, KeyringError> {
+        let Some(key) = &self.enabled_key else {
+            return Ok(true);
+        };
+        match self.config_figment.extract_inner::<bool>(key) {
+            Ok(enabled) => Ok(enabled),
+            Err(e) if e.missing() => Ok(false),
+            Err(e) => Err(KeyringError::ConfigError(format!(
+                "'{}' must be a boolean: {}",
+                key, e
+            ))),
+        }
+    }
+
+    fn disabled(&self) -> Map<Profile, Dict> {
+        let mut map = Map::new();
+        map.insert(self.target_profile(), Dict::new());
+        map
+    }
+
+    fn target_profile(&self) -> Profile {
+        match &self.profile_selector {
+            Some(selector) => selector(&self.config_figment),
+            None => self.profile.clone().unwrap_or_default(),
+        }
+    }
+
+    fn in_profile(&self, secret: Option<Vec<u8>>) -> Vec<(Profile, Vec<u8>)> {
+        let profile = self.target_profile();
+        secret.map(|value| (profile, value)).into_iter().collect()
+    }
+
+    /// Emit the `found` secrets, read as `source` when there is a single
+    /// one.
+    fn output(
+        &self,
+        config: &KeyringConfig,
+        found: Vec<(Profile, Vec<u8>)>,
+        source: Option<&Resolution>,
+    ) -> std::result::Result<Map<Profile, Dict>, KeyringError> {
+        if found.is_empty() && !config.optional {
+            return Err(KeyringError::NotFound(self.credential_name.clone()));
+        }
+
+        let key = self.emitted_key();
+        let mut map = Map::new();
+        if found.is_empty() {
+            map.insert(self.target_profile(), Dict::new());
+        }
+        for (profile, value) in found {
+            let dict = map.entry(profile).or_insert_with(Dict::new);
+            self.emit(config, dict, key, value)?;
+            if self.append_key.is_some()
+                && let Some(value) = dict.remove(key)
+            {
+                dict.insert(key.clone(), Value::from(vec![value]));
+            }
+            if self.credential_form
+                && let Some(password) = dict.remove(key)
+            {
+                let username = self.credential_username(config, source);
+                let credential = Dict::from([
+                    ("username".to_string(), Value::from(username)),
+                    ("password".to_string(), password),
+                ]);
+                dict.insert(key.clone(), Value::from(credential));
+            }
+            if self.source_key
+                && let Some(source) = source
+            {
+                let keyring = source.keyring.as_str();
+                dict.insert(format!("{}_source", key), Value::from(keyring));
+            }
+            self.nest_keys(dict, &[key.clone(), format!("{}_source", key)]);
+        }
+
+        Ok(self.finish(config, map))
+    }
+
+    /// Username the secret was stored under, as reported by the backend
+    /// entry, falling back to the name it was looked up by.
+    fn credential_username(&self, config: &KeyringConfig, source: Option<&Resolution>) -> String {
+        let Some(resolution) = source else {
+            return self.credential_name.clone();
+        };
+        self.entry(config, &resolution.keyring, &resolution.credential)
+            .ok()
+            .and_then(|entry| entry.get_specifiers())
+            .map(|(_, username)| username)
+            .unwrap_or_else(|| resolution.credential.clone())
+    }
+
+    /// Nest the provider's own `keys` in `dict` on the key separator,
+    /// leaving every other key as it is.
+    fn nest_keys(&self, dict: &mut Dict, keys: &[String]) {
+        let Some(separator) = &self.key_separator else {
+            return;
+        };
+        let own: Dict = keys
+            .iter()
+            .filter_map(|key| dict.remove_entry(key))
+            .collect();
+        json::deep_merge(dict, json::nest(own, separator));
+    }
+
+    /// Apply the options that reshape the emitted map.
+    fn finish(&self, config: &KeyringConfig, mut map: Map<Profile, Dict>) -> Map<Profile, Dict> {
+        if self.namespaced {
+            for dict in map.values_mut() {
+                let inner = std::mem::take(dict);
+                dict.insert(config.service.clone(), Value::from(inner));
+            }
+        }
+
+        let profile = self.target_profile();
+        if self.also_default
+            && profile != Profile::Default
+            && profile != Profile::Global
+            && let Some(dict) = map.get(&profile).cloned()
+        {
+            json::deep_merge(map.entry(Profile::Default).or_default(), dict);
+        }
+        map
+    }
+
+    fn load_manifest(&self, config: &KeyringConfig) -> (Dict, Vec<(String, KeyringError)>) {
+        let mut loaded = Dict::new();
+        let mut failures = Vec::new();
+        for entry in &config.credentials {
+            let name = &entry.name;
+            let result = match self.fetch_name(config, name) {
+                Ok(Some(secret)) => self.emit(config, &mut loaded, name, secret),
+                Ok(None) if entry.is_optional(config) => Ok(()),
+                Ok(None) => Err(KeyringError::NotFound(name.clone())),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                failures.push((name.clone(), e));
+            }
+        }
+        (loaded, failures)
+    }
+
+    fn load_specs(&self, config: &KeyringConfig) -> std::result::Result<Dict, KeyringError> {
+        let mut loaded = Dict::new();
+        let mut keys = Vec::new();
+        for spec in &config.secrets {
+            let spec_config = KeyringConfig {
+                service: spec.service.clone(),
+                keyrings: vec![spec.keyring.clone()],
+                optional: spec.optional || config.optional,
+                ..config.clone()
+            };
+            let key = spec.config_key.as_ref().unwrap_or(&spec.credential);
+            keys.push(key.clone());
+            match self.get_from_keyring(&spec_config, &spec.keyring, &spec.credential) {
+                Ok(secret) => self.emit(&spec_config, &mut loaded, key, secret)?,
+                Err(e) if skippable(&spec_config, &spec.keyring, &e) => {
+                    if !spec_config.optional {
+                        return Err(KeyringError::NotFound(spec.credential.clone()));
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        self.nest_keys(&mut loaded, &keys);
+        Ok(loaded)
+    }
+
+    fn merge_parts(&self, config: &KeyringConfig) -> std::result::Result<Dict, KeyringError> {
+        let mut merged = Dict::new();
+        for part in &self.json_parts {
+            match self.fetch_name(config, part)? {
+                Some(secret) => json::deep_merge(&mut merged, json::parse_object(&secret, part)?),
+                None if config.optional => continue,
+                None => return Err(KeyringError::NotFound(part.clone())),
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Split a JSON map of tenant secrets into one profile per tenant.
+    fn search_tenants(
+        &self,
+        config: &KeyringConfig,
+        key: &str,
+    ) -> std::result::Result<Map<Profile, Dict>, KeyringError> {
+        let Some((resolution, secret)) = self.search_chain(config)? else {
+            return self.output(config, Vec::new(), None);
+        };
+        if let Some(expected) = &config.expected_sha256 {
+            integrity::verify_sha256(&secret, expected)?;
+        }
+        if let Some(resolution) = &resolution {
+            self.mirror(config, resolution, &secret);
+        }
+        let secret = self.prepare(secret, &self.credential_name)?;
+        let tenants = json::parse_object(&secret, &self.credential_name)?;
+        let map = tenants
+            .into_iter()
+            .map(|(tenant, value)| {
+                let profile = Profile::from(tenant.as_str());
+                (profile, Dict::from([(key.to_string(), value)]))
+            })
+            .collect();
+        if let Some(resolution) = &resolution {
+            self.consume(config, resolution);
+        }
+        Ok(map)
+    }
+
+    /// Turn a stored secret into the value to hand out.
+    fn prepare(&self, secret: Vec<u8>, name: &str) -> std::result::Result<Vec<u8>, KeyringError> {
+        let secret = encoding::decode(secret, self.encoding, name)?;
+        // Stripping works on the text, so it follows the charset decode.
+        #[cfg(feature = "encoding")]
+        let mut secret = self.charset.decode(secret, name)?;
+        #[cfg(not(feature = "encoding"))]
+        let mut secret = secret;
+        if self.strip_bom && secret.starts_with(UTF8_BOM) {
+            secret.drain(..UTF8_BOM.len());
+        }
+        if self.strip_newline {
+            if secret.ends_with(b"\r\n") {
+                secret.truncate(secret.len() - 2);
+            } else if secret.ends_with(b"\n") {
+                secret.pop();
+            }
+        }
+        Ok(secret)
+    }
+
+    fn emit(
+        &self,
+        config: &KeyringConfig,
+        dict: &mut Dict,
+        key: &str,
+        secret: Vec<u8>,
+    ) -> std::result::Result<(), KeyringError> {
+        self.to_value(config, secret, key)?.insert_into(dict, key);
+        Ok(())
+    }
+
+    /// Turn a stored secret into the value emitted under `key`.
+    fn to_value(
+        &self,
+        config: &KeyringConfig,
+        secret: Vec<u8>,
+        key: &str,
+    ) -> std::result::Result<KeyringValue, KeyringError> {
+        let secret = self.prepare(secret, key)?;
+        let secret = match String::from_utf8(secret) {
+            Ok(secret) => secret,
+            Err(e) => match config.on_invalid_utf8 {
+                InvalidUtf8::Lossy => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+                InvalidUtf8::Bytes if !self.dotenv => {
+                    return Ok(KeyringValue::Bytes(e.into_bytes()));
+                }
+                InvalidUtf8::Error | InvalidUtf8::Bytes => {
+                    return Err(KeyringError::ParseError(format!(
+                        "secret '{}' is not valid UTF-8 (invalid byte at offset {})",
+                        key,
+                        e.utf8_error().valid_up_to()
+                    )));
+                }
+            },
+        };
+
+        Ok(if self.dotenv {
+            KeyringValue::Entries(dotenv::parse(&secret)?)
+        } else if let Some(delimiter) = self.list_delimiter {
+            KeyringValue::List(
+                secret
+                    .split(delimiter)
+                    .map(str::trim)
+                    .filter(|part| !part.is_empty())
+                    .map(String::from)
+                    .collect(),
+            )
+        } else if let Some(kind) = config.coerce {
+            KeyringValue::Parsed(kind.coerce(&secret, key)?)
+        } else {
+            KeyringValue::String(secret)
+        })
+    }
+
+    fn extract_config(&self) -> std::result::Result<KeyringConfig, KeyringError> {
+        let mut config = KeyringConfig::from_figment(&self.config_figment);
+        if let Some((attempts, reload)) = &self.config_retry {
+            for _ in 0..*attempts {
+                let Err(e) = &config else { break };
+                log::debug!("retrying config extraction after error: {}", e);
+                config = KeyringConfig::from_figment(&reload());
+            }
+        }
+        let mut config = config?;
+        config.strict_errors |= self.strict_errors;
+        Ok(match &self.config_mapper {
+            Some(mapper) => mapper(config),
+            None => config,
+        })
+    }
+
+    /// Try the fallback chain in order; the resolution is set when the
+    /// secret came from a keyring.
+    fn search_chain(
+        &self,
+        config: &KeyringConfig,
+    ) -> std::result::Result<Option<ChainSecret>, KeyringError> {
+        if self.fallback_chain.is_empty() {
+            return Ok(self
+                .search_keyrings(config)?
+                .map(|(resolution, secret)| (Some(resolution), secret)));
+        }
+        for source in &self.fallback_chain {
+            let found = match source {
+                FallbackSource::Keyring => self
+                    .search_keyrings(config)?
+                    .map(|(resolution, secret)| (Some(resolution), secret)),
+                _ => source.read()?.map(|secret| (None, secret)),
+            };
+            if found.is_some() {
+                return Ok(found);
+            }
+        }
+        Ok(None)
+    }
+
+    fn search_keyrings(
+        &self,
+        config: &KeyringConfig,
+    ) -> std::result::Result<Option<(Resolution, Vec<u8>)>, KeyringError> {
+        if let Some(hit) = self.cached(None) {
+            return Ok(Some(hit));
+        }
+        if self.missed(None) {
+            return Ok(None);
+        }
+        let found = self.search_uncached(config)?;
+        match &found {
+            Some((resolution, secret)) => {
+                self.see(secret);
+                if self.cache_ttl.is_some() {
+                    self.remember(None, resolution.clone(), secret.clone());
+                }
+            }
+            None => self.remember_miss(None),
+        }
+        Ok(found)
+    }
+
+    /// [`search_name`](Self::search_name) through the cache.
+    fn fetch_name(
+        &self,
+        config: &KeyringConfig,
+        name: &str,
+    ) -> std::result::Result<Option<Vec<u8>>, KeyringError> {
+        if let Some((_, secret)) = self.cached(Some(name)) {
+            return Ok(Some(secret));
+        }
+        if self.missed(Some(name)) {
+            return Ok(None);
+        }
+        let found = self.locate_name(config, name)?;
+        match &found {
+            Some((resolution, secret)) if self.cache_ttl.is_some() => {
+                self.remember(Some(name.to_string()), resolution.clone(), secret.clone());
+            }
+            Some(_) => {}
+            None => self.remember_miss(Some(name)),
+        }
+        Ok(found.map(|(_, secret)| secret))
+    }
+
+    fn cached(&self, name: Option<&str>) -> Option<(Resolution, Vec<u8>)> {
+        let cache = self.cache.lock().expect("secret cache poisoned");
+        let (fetched, resolution, secret) = cache.get(&name.map(str::to_string))?;
+        match self.cache_ttl {
+            Some(ttl) if self.clock.now().duration_since(*fetched) >= ttl => None,
+            _ => Some((resolution.clone(), secret.clone())),
+        }
+    }
+
+    fn remember(&self, name: Option<String>, resolution: Resolution, secret: Vec<u8>) {
+        let now = self.clock.now();
+        self.cache
+            .lock()
+            .expect("secret cache poisoned")
+            .insert(name, (now, resolution, secret));
+    }
+
+    fn search_uncached(
+        &self,
+        config: &KeyringConfig,
+    ) -> std::result::Result<Option<(Resolution, Vec<u8>)>, KeyringError> {
+        let lookups = self.versioned_lookups(config)?;
+        for (i, resolution) in lookups.iter().enumerate() {
+            match self.get_from_keyring(config, &resolution.keyring, &resolution.credential) {
+                Ok(secret) => {
+                    if config.detect_drift {
+                        self.check_drift(config, resolution, &secret, &lookups[i + 1..])?;
+                    }
+                    return Ok(Some((resolution.clone(), secret)));
+                }
+                Err(e) if skippable(config, &resolution.keyring, &e) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Fail when a later keyring in `rest` holds the found credential with a
+    /// different value.
+    fn check_drift(
+        &self,
+        config: &KeyringConfig,
+        found: &Resolution,
+        secret: &[u8],
+        rest: &[Resolution],
+    ) -> std::result::Result<(), KeyringError> {
+        let others = rest
+            .iter()
+            .filter(|other| other.credential == found.credential && other.keyring != found.keyring);
+        for other in others {
+            match self.get_from_keyring(config, &other.keyring, &other.credential) {
+                Ok(value) if value != secret => {
+                    return Err(KeyringError::BackendError(format!(
+                        "value drift detected across keyrings ('{}' differs between {} and {})",
+                        found.credential,
+                        found.keyring.as_str(),
+                        other.keyring.as_str()
+                    )));
+                }
+                Ok(_) => {}
+                Err(e) if skippable(config, &other.keyring, &e) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// [`lookups`](Self::lookups), redirected to the current version when a
+    /// version pointer is set and present.
+    fn versioned_lookups(
+        &self,
+        config: &KeyringConfig,
+    ) -> std::result::Result<Vec<Resolution>, KeyringError> {
+        Ok(match self.current_version(config)? {
+            Some(name) => self
+                .read_order(config)
+                .into_iter()
+                .map(|keyring| Resolution {
+                    keyring,
+                    credential: name.clone(),
+                    fallback: false,
+                })
+                .collect(),
+            None => self.lookups(config),
+        })
+    }
+
+    /// Versioned credential name selected by the version pointer, if any.
+    fn current_version(
+        &self,
+        config: &KeyringConfig,
+    ) -> std::result::Result<Option<String>, KeyringError> {
+        let Some(suffix) = &self.version_pointer else {
+            return Ok(None);
+        };
+        let pointer = format!("{}.{}", self.credential_name, suffix);
+        let Some(version) = self.search_name(config, &pointer)? else {
+            return Ok(None);
+        };
+        let version = String::from_utf8(version).map_err(|_| {
+            KeyringError::ParseError(format!("version pointer '{}' is not valid UTF-8", pointer))
+        })?;
+        Ok(Some(format!("{}.{}", self.credential_name, version.trim())))
+    }
+
+    fn search_name(
+        &self,
+        config: &KeyringConfig,
+        name: &str,
+    ) -> std::result::Result<Option<Vec<u8>>, KeyringError> {
+        Ok(self.locate_name(config, name)?.map(|(_, secret)| secret))
+    }
+
+    fn locate_name(
+        &self,
+        config: &KeyringConfig,
+        name: &str,
+    ) -> std::result::Result<Option<(Resolution, Vec<u8>)>, KeyringError> {
+        for keyring in &config.search_order() {
+            match self.get_from_keyring(config, keyring, name) {
+                Ok(secret) => {
+                    let resolution = Resolution {
+                        keyring: keyring.clone(),
+                        credential: name.to_string(),
+                        fallback: false,
+                    };
+                    return Ok(Some((resolution, secret)));
+                }
+                Err(e) if skippable(config, keyring, &e) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// The config's search order, rearranged by its `read_strategy`.
+    fn read_order(&self, config: &KeyringConfig) -> Vec<Keyring> {
+        let mut order = config.search_order();
+        if order.is_empty() {
+            return order;
+        }
+        match config.read_strategy {
+            ReadStrategy::FirstWins => {}
+            ReadStrategy::RoundRobin => {
+                let start = self.read_counter.fetch_add(1, Ordering::Relaxed) % order.len();
+                order.rotate_left(start);
+            }
+            ReadStrategy::Random => {
+                let mut state = self.read_rng.lock().expect("read rng poisoned");
+                for i in (1..order.len()).rev() {
+                    let j = next_random(&mut state) as usize % (i + 1);
+                    order.swap(i, j);
+                }
+            }
+        }
+        order
+    }
+
+    /// Keyring and credential name pairs to try, in order.
+    fn lookups(&self, config: &KeyringConfig) -> Vec<Resolution> {
+        let order = self.read_order(config);
+        let primary = order.iter().flat_map(|keyring| {
+            let names = self.name_fallbacks.iter().map(|name| Resolution {
+                keyring: keyring.clone(),
+                credential: name.clone(),
+                fallback: true,
+            });
+            std::iter::once(Resolution {
+                keyring: keyring.clone(),
+                credential: self.username_for(keyring).to_string(),
+                fallback: false,
+            })
+            .chain(names)
+        });
+        let fallback = self.rotation_fallback.iter().flat_map(|name| {
+            order.iter().map(move |keyring| Resolution {
+                keyring: keyring.clone(),
+                credential: name.clone(),
+                fallback: true,
+            })
+        });
+        primary.chain(fallback).collect()
+    }
+
+    fn search_profiles(
+        &self,
+        config: &KeyringConfig,
+        delimiter: char,
+    ) -> std::result::Result<Vec<(Profile, Vec<u8>)>, KeyringError> {
+        let mut found: Vec<(Profile, Vec<u8>)> = Vec::new();
+        for keyring in &config.search_order() {
+            let entries = match self.list_entries(config, keyring) {
+                Ok(entries) => entries,
+                Err(_) if config.tolerates_errors(keyring) => continue,
+                Err(e) => return Err(e),
+            };
+            for entry in entries {
+                let Some((service, username)) = entry.get_specifiers() else {
+                    continue;
+                };
+                if service != config.service {
+                    continue;
+                }
+
+                let base = self.username_for(keyring);
+                let profile = if username == base {
+                    self.target_profile()
+                } else {
+                    match username
+                        .strip_prefix(base)
+                        .and_then(|rest| rest.strip_prefix(delimiter))
+                    {
+                        Some(suffix) if !suffix.is_empty() => Profile::from(suffix),
+                        _ => continue,
+                    }
+                };
+                if found.iter().any(|(p, _)| *p == profile) {
+                    continue;
+                }
+
+                match keyring_config::backend::read_secret_bytes(&entry)
+                    .and_then(|secret| config.check_secret_len(secret))
+                {
+                    Ok(secret) => found.push((profile, secret)),
+                    Err(e) if skippable(config, keyring, &e) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Profile and credential name of each
+    /// [`expand_profiles`](Self::expand_profiles) suffix.
+    fn suffixed_names(&self) -> impl Iterator<Item = (Profile, String)> + '_ {
+        let delimiter = self.profile_delimiter.unwrap_or('.');
+        self.profile_suffixes.iter().map(move |suffix| {
+            let name = format!("{}{}{}", self.credential_name, delimiter, suffix);
+            (Profile::from(suffix.as_str()), name)
+        })
+    }
+
+    fn search_suffixes(
+        &self,
+        config: &KeyringConfig,
+    ) -> std::result::Result<Vec<(Profile, Vec<u8>)>, KeyringError> {
+        let mut found = Vec::new();
+        for (profile, name) in self.suffixed_names() {
+            match self.fetch_name(config, &name)? {
+                Some(secret) => found.push((profile, secret)),
+                None if config.optional => continue,
+                None => return Err(KeyringError::NotFound(name)),
+            }
+        }
+        Ok(found)
+    }
+
+    fn search_prefix(
+        &self,
+        config: &KeyringConfig,
+        prefix: &str,
+    ) -> std::result::Result<Map<Profile, Dict>, KeyringError> {
+        let mut dict = Dict::new();
+        for keyring in &config.search_order() {
+            let entries = match self.list_entries(config, keyring) {
+                Ok(entries) => entries,
+                Err(e @ KeyringError::Unsupported(_)) => return Err(e),
+                Err(_) if config.tolerates_errors(keyring) => continue,
+                Err(e) => return Err(e),
+            };
+            for entry in entries {
+                let Some((service, username)) = entry.get_specifiers() else {
+                    continue;
+                };
+                let Some(key) = username.strip_prefix(prefix) else {
+                    continue;
+                };
+                if service != config.service || key.is_empty() || dict.contains_key(key) {
+                    continue;
+                }
+
+                match keyring_config::backend::read_secret_bytes(&entry)
+                    .and_then(|secret| config.check_secret_len(secret))
+                {
+                    Ok(secret) => self.emit(config, &mut dict, key, secret)?,
+                    Err(e) if skippable(config, keyring, &e) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        if dict.is_empty() && !config.optional {
+            return Err(KeyringError::NotFound(prefix.into()));
+        }
+
+        let dict = match &self.config_key {
+            Some(key) => Dict::from([(key.clone(), Value::from(dict))]),
+            None => dict,
+        };
+        let mut map = Map::new();
+        map.insert(self.target_profile(), dict);
+        Ok(map)
+    }
+
+    fn list_entries(
+        &self,
+        config: &KeyringConfig,
+        keyring: &Keyring,
+    ) -> std::result::Result<Vec<keyring_core::Entry>, KeyringError> {
+        let modifiers = keyring_config::backend::entry_modifiers(config, keyring);
+        match &self.entry_search {
+            Some(search) => search(keyring, &config.service, &modifiers),
+            None => keyring_config::backend::search_entries_in(
+                config.store.as_deref(),
+                keyring,
+                &config.service,
+                &modifiers,
+            ),
+        }
+    }
+
+    /// Record the digest of the provider's own secret as last read, returning
+    /// whether it differs from the one recorded before.
+    fn see(&self, secret: &[u8]) -> bool {
+        let digest = integrity::sha256(secret);
+        let mut seen = self.seen_digest.lock().expect("seen digest poisoned");
+        seen.replace(digest) != Some(digest)
+    }
+
+    /// Whether a miss for `name` was recorded within the negative TTL.
+    fn missed(&self, name: Option<&str>) -> bool {
+        let Some(ttl) = self.negative_ttl else {
+            return false;
+        };
+        let misses = self.misses.lock().expect("miss cache poisoned");
+        misses
+            .get(&name.map(str::to_string))
+            .is_some_and(|missed| self.clock.now().duration_since(*missed) < ttl)
+    }
+
+    fn remember_miss(&self, name: Option<&str>) {
+        if self.negative_ttl.is_none() {
+            return;
+        }
+        let now = self.clock.now();
+        self.misses
+            .lock()
+            .expect("miss cache poisoned")
+            .insert(name.map(str::to_string), now);
+    }
+
+    fn clear_cache(&self) {
+        self.cache.lock().expect("secret cache poisoned").clear();
+        self.misses.lock().expect("miss cache poisoned").clear();
+    }
+
+    fn mirror(&self, config: &KeyringConfig, resolution: &Resolution, secret: &[u8]) {
+        let Some(target) = &self.mirror_target else {
+            return;
+        };
+        if resolution.keyring == *target {
+            return;
+        }
+
+        let mirrored = config.check_writable(target).and_then(|_| {
+            self.entry(config, target, self.username_for(target))
+                .and_then(
+                    |entry| match keyring_config::backend::read_secret_bytes(&entry) {
+                        Ok(_) => Ok(()),
+                        Err(KeyringError::NotFound(_)) => {
+                            keyring_config::backend::write_secret_bytes(&entry, secret)
+                        }
+                        Err(e) => Err(e),
+                    },
+                )
+        });
+        if let Err(e) = mirrored {
+            log::warn!(
+                "failed to mirror '{}' into {} keyring: {}",
+                resolution.credential,
+                target.as_str(),
+                e
+            );
+        }
+    }
+
+    fn consume(&self, config: &KeyringConfig, resolution: &Resolution) {
+        if !self.consume {
+            return;
+        }
+        self.clear_cache();
+        let deleted = config.check_writable(&resolution.keyring).and_then(|_| {
+            self.entry(config, &resolution.keyring, &resolution.credential)
+                .and_then(|entry| keyring_config::backend::delete_secret(&entry))
+        });
+        if let Err(e) = deleted {
+            log::warn!(
+                "failed to delete consumed '{}' from {} keyring: {}",
+                resolution.credential,
+                resolution.keyring.as_str(),
+                e
+            );
+        }
+    }
+
+    fn get_from_keyring(
+        &self,
+        config: &KeyringConfig,
+        keyring: &Keyring,
+        username: &str,
+    ) -> std::result::Result<Vec<u8>, KeyringError> {
+        match self.read_from_keyring(config, keyring, username) {
+            Err(e) if config.is_transient(&e) => {
+                log::debug!("retrying {} after transient error: {}", keyring.as_str(), e);
+                self.read_from_keyring(config, keyring, username)
+            }
+            result => result,
+        }
+        .and_then(|secret| config.check_secret_len(secret))
+    }
+
+    fn read_from_keyring(
+        &self,
+        config: &KeyringConfig,
+        keyring: &Keyring,
+        username: &str,
+    ) -> std::result::Result<Vec<u8>, KeyringError> {
+        let Some(breaker) = &self.breaker else {
+            return self.read_entry(config, keyring, username);
+        };
+
+        let now = self.clock.now();
+        {
+            let mut states = breaker.states.lock().expect("circuit breaker poisoned");
+            let state = states.entry(keyring.clone()).or_default();
+            match state.open_until {
+                Some(until) if now < until => {
+                    return Err(KeyringError::CircuitOpen(keyring.as_str().into()));
+                }
+                Some(_) => *state = BreakerState::default(),
+                None => {}
+            }
+        }
+
+        let result = self.read_entry(config, keyring, username);
+        let mut states = breaker.states.lock().expect("circuit breaker poisoned");
+        let state = states.entry(keyring.clone()).or_default();
+        match &result {
+            Ok(_) | Err(KeyringError::NotFound(_)) => state.failures = 0,
+            Err(_) => {
+                state.failures += 1;
+                if state.failures >= breaker.failure_threshold {
+                    state.open_until = Some(now + breaker.cooldown);
+                }
+            }
+        }
+        result
+    }
+
+    fn read_entry(
+        &self,
+        config: &KeyringConfig,
+        keyring: &Keyring,
+        username: &str,
+    ) -> std::result::Result<Vec<u8>, KeyringError> {
+        let entry = self.entry(config, keyring, username)?;
+        if let Some(hook) = &self.access_hook {
+            hook(keyring, &config.service, username);
+        }
+        let policy = config.on_ambiguous;
+        let Some(timeout) = self.timeout else {
+            return keyring_config::backend::read_unambiguous(&entry, policy);
+        };
+        self.read_worker.read(timeout, move || {
+            keyring_config::backend::read_unambiguous(&entry, policy)
+        })
+    }
+
+    fn write_entry(&self) -> std::result::Result<keyring_core::Entry, KeyringError> {
+        let config = self.extract_config()?;
+        let first = config
+            .keyrings
+            .first()
+            .ok_or_else(|| KeyringError::ConfigError("no keyrings configured".into()))?;
+        let keyring = config
+            .keyrings
+            .iter()
+            .find(|keyring| config.role(keyring).can_write())
+            .unwrap_or(first);
+        config.check_writable(keyring)?;
+        self.entry(&config, keyring, self.username_for(keyring))
+    }
+
+    fn entry(
+        &self,
+        config: &KeyringConfig,
+        keyring: &Keyring,
+        username: &str,
+    ) -> std::result::Result<keyring_core::Entry, KeyringError> {
+        if *keyring == Keyring::System && config.check_privileges && !(self.privilege_check)()? {
+            return Err(KeyringError::PermissionDenied(
+                "system keyring requires elevated privileges".into(),
+            ));
+        }
+
+        let username = config.name_case.apply(username);
+        let names = match &self.naming {
+            Some(naming) => naming.backend_names(&config.service, &username),
+            None => naming::Verbatim.backend_names(&config.service, &username),
+        };
+        let mut modifiers: HashMap<&str, &str> =
+            keyring_config::backend::entry_modifiers(config, keyring);
+        modifiers.extend(
+            names
+                .modifiers
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        );
+        if let Some(context) = &self.access_context {
+            modifiers.insert(keyring_config::backend::ACCESS_REASON_MODIFIER, context);
+        }
+        let (service, username) = if self.swap_names {
+            (&names.username, &names.service)
+        } else {
+            (&names.service, &names.username)
+        };
+        match &self.entry_factory {
+            Some(factory) => factory(keyring, service, username, &modifiers),
+            None => keyring_config::backend::create_entry_in(
+                config.store.as_deref(),
+                keyring,
+                service,
+                username,
+                &modifiers,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use keyring_core::api::CredentialStoreApi;
+    use keyring_core::mock;
+    use std::sync::Mutex;
+
+    pub(crate) fn mock_factory(store: Arc<mock::Store>) -> EntryFactory {
+        Arc::new(move |_keyring, service, username, _modifiers| {
+            store
+                .build(service, username, None)
+                .map_err(|e| KeyringError::BackendError(e.to_string()))
+        })
+    }
+
+    /// The keyring, service, username and modifiers of one entry request.
+    pub(crate) type FactoryCall = (Keyring, String, String, HashMap<String, String>);
+
+    /// A [`mock_factory`] that also records every entry it is asked for.
+    pub(crate) fn recording_factory(
+        store: Arc<mock::Store>,
+    ) -> (EntryFactory, Arc<Mutex<Vec<FactoryCall>>>) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let inner = mock_factory(store);
+        let factory: EntryFactory = Arc::new(move |keyring, service, username, modifiers| {
+            recorded.lock().unwrap().push((
+                keyring.clone(),
+                service.to_string(),
+                username.to_string(),
+                modifiers
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ));
+            inner(keyring, service, username, modifiers)
+        });
+        (factory, calls)
+    }
+
+    fn mock_keyrings_factory(stores: Vec<(Keyring, Arc<mock::Store>)>) -> EntryFactory {
+        Arc::new(move |keyring, service, username, _modifiers| {
+            let (_, store) = stores
+                .iter()
+                .find(|(k, _)| k == keyring)
+                .ok_or_else(|| KeyringError::ServiceUnavailable(format!("{:?}", keyring)))?;
+            store
+                .build(service, username, None)
+                .map_err(|e| KeyringError::BackendError(e.to_string()))
+        })
+    }
+
+    fn keyrings_figment(service: &str, keyrings: &[&str], optional: bool) -> Figment {
+        Figment::from(Serialized::defaults(KeyringConfig {
+            service: service.into(),
+            keyrings: keyrings.iter().map(|k| Keyring::from(*k)).collect(),
+            optional,
+            ..Default::default()
+        }))
+    }
+
+    fn invalid_utf8_provider(mode: InvalidUtf8) -> KeyringProvider {
+        let store = mock::Store::new().unwrap();
+        store
+            .build("test-app", "api_key", None)
+            .unwrap()
+            .set_secret(&[b'o', b'k', 0xff, b'!'])
+            .unwrap();
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            on_invalid_utf8: mode,
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(mock_factory(store))
+    }
+
+    fn mock_search(store: Arc<mock::Store>) -> EntrySearch {
+        Arc::new(move |_keyring, service, _modifiers| {
+            let spec = std::collections::HashMap::from([("service", service)]);
+            store
+                .search(&spec)
+                .map_err(|e| KeyringError::BackendError(e.to_string()))
+        })
+    }
+
+    fn mock_store_with(entries: &[(&str, &str, &str)]) -> Arc<mock::Store> {
+        let store = mock::Store::new().unwrap();
+        for (service, username, secret) in entries {
+            store
+                .build(service, username, None)
+                .unwrap()
+                .set_password(secret)
+                .unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn test_keyring_from_str() {
+        assert_eq!(Keyring::from("user"), Keyring::User);
+        assert_eq!(Keyring::from("system"), Keyring::System);
+        assert_eq!(Keyring::from("memory"), Keyring::Memory);
+        assert_eq!(
+            Keyring::from("custom-keyring"),
+            Keyring::Named("custom-keyring".into())
+        );
+    }
+
+    #[test]
+    fn test_keyring_default() {
+        assert_eq!(Keyring::default(), Keyring::User);
+    }
+
+    #[test]
+    fn test_keyring_provider_new() {
+        let provider = KeyringProvider::new("test-app", "test-key");
+        assert_eq!(provider.credential_name, "test-key");
+    }
+
+    #[test]
+    fn test_keyring_provider_system() {
+        let provider = KeyringProvider::system("test-app", "test-key");
+        assert_eq!(provider.credential_name, "test-key");
+    }
+
+    #[test]
+    fn test_keyring_provider_as_key() {
+        let provider = KeyringProvider::new("test-app", "test-key").as_key("custom.config.key");
+        assert_eq!(provider.config_key, Some("custom.config.key".into()));
+    }
+
+    #[test]
+    fn test_keyring_provider_with_profile() {
+        let profile = Profile::from("production");
+        let provider = KeyringProvider::new("test-app", "test-key").with_profile(profile.clone());
+        assert_eq!(provider.profile, Some(profile));
+    }
+
+    #[test]
+    fn test_keyring_provider_focused() {
+        let config_figment = Figment::new();
+        let provider = KeyringProvider::configured_by(config_figment, "api_key");
+        let focused_provider = provider.focused("keyring");
+        assert_eq!(focused_provider.credential_name, "api_key");
+        assert_eq!(focused_provider.config_key, None);
+        assert_eq!(focused_provider.profile, None);
+    }
+
+    #[test]
+    fn test_keyring_provider_focused_preserves_config_key() {
+        let config_figment = Figment::new();
+        let provider =
+            KeyringProvider::configured_by(config_figment, "api_key").as_key("custom_key");
+        let focused_provider = provider.focused("keyring");
+        assert_eq!(focused_provider.config_key, Some("custom_key".into()));
+    }
+
+    #[test]
+    fn test_keyring_provider_focused_preserves_profile() {
+        let config_figment = Figment::new();
+        let profile = Profile::from("production");
+        let provider =
             KeyringProvider::configured_by(config_figment, "api_key").with_profile(profile.clone());
         let focused_provider = provider.focused("keyring");
         assert_eq!(focused_provider.profile, Some(profile));
     }
+
+    #[test]
+    fn test_keyring_provider_entry_factory() {
+        let store = mock::Store::new().unwrap();
+        store
+            .build("test-app", "api_key", None)
+            .unwrap()
+            .set_password("s3cret")
+            .unwrap();
+
+        let (factory, calls) = recording_factory(store);
+        let provider = KeyringProvider::new("test-app", "api_key").with_entry_factory(factory);
+
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("s3cret"));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![(
+                Keyring::User,
+                "test-app".to_string(),
+                "api_key".to_string(),
+                HashMap::new()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_keyring_provider_focused_preserves_entry_factory() {
+        let store = mock::Store::new().unwrap();
+        let provider = KeyringProvider::configured_by(Figment::new(), "api_key")
+            .with_entry_factory(mock_factory(store))
+            .focused("keyring");
+        assert!(provider.entry_factory.is_some());
+    }
+
+    #[test]
+    fn test_keyring_provider_split_profile_on() {
+        let store = mock_store_with(&[
+            ("test-app", "api_key@production", "prod-secret"),
+            ("test-app", "api_key@staging", "staging-secret"),
+            ("test-app", "other@production", "unrelated"),
+        ]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store.clone()))
+            .with_entry_search(mock_search(store))
+            .split_profile_on('@');
+
+        let data = provider.data().unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(
+            data[&Profile::from("production")]["api_key"].as_str(),
+            Some("prod-secret")
+        );
+        assert_eq!(
+            data[&Profile::from("staging")]["api_key"].as_str(),
+            Some("staging-secret")
+        );
+    }
+
+    #[test]
+    fn test_expand_profiles() {
+        let store = mock_store_with(&[
+            ("test-app", "api_key.staging", "staging-secret"),
+            ("test-app", "api_key.production", "production-secret"),
+            ("test-app", "api_key", "default-secret"),
+        ]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .expand_profiles(vec!["staging", "production"]);
+
+        let data = provider.data().unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(
+            data[&Profile::from("staging")]["api_key"].as_str(),
+            Some("staging-secret")
+        );
+        assert_eq!(
+            data[&Profile::from("production")]["api_key"].as_str(),
+            Some("production-secret")
+        );
+
+        let missing = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(mock::Store::new().unwrap()))
+            .expand_profiles(vec!["staging"]);
+        assert!(matches!(
+            missing.load(),
+            Err(KeyringError::NotFound(name)) if name == "api_key.staging"
+        ));
+    }
+
+    #[test]
+    fn test_load_prefix() {
+        let store = mock_store_with(&[
+            ("test-app", "feature.beta", "on"),
+            ("test-app", "feature.dark_mode", "off"),
+            ("test-app", "api_key", "unrelated"),
+            ("other-app", "feature.beta", "other"),
+        ]);
+        let provider = KeyringProvider::load_prefix("test-app", "feature.")
+            .with_entry_factory(mock_factory(store.clone()))
+            .with_entry_search(mock_search(store))
+            .as_key("features");
+
+        let data = provider.data().unwrap();
+        let features = data[&Profile::Default]["features"].as_dict().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(features["beta"].as_str(), Some("on"));
+        assert_eq!(features["dark_mode"].as_str(), Some("off"));
+    }
+
+    #[test]
+    fn test_load_prefix_unsupported() {
+        let provider =
+            KeyringProvider::load_prefix("test-app", "feature.").with_entry_search(Arc::new(
+                |_keyring, _service, _modifiers| Err(KeyringError::Unsupported("no search".into())),
+            ));
+
+        let err = provider.data().unwrap_err();
+        assert_eq!(err.to_string(), "unsupported operation: no search");
+    }
+
+    #[test]
+    fn test_read_secret_missing_entry_is_not_found() {
+        let store = mock_store_with(&[]);
+        let entry = store.build("test-app", "api_key", None).unwrap();
+        assert!(matches!(
+            keyring_config::backend::read_secret(&entry),
+            Err(KeyringError::NotFound(name)) if name == "api_key"
+        ));
+    }
+
+    #[test]
+    fn test_keyring_provider_for_all_profiles() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .for_all_profiles();
+
+        let data = provider.data().unwrap();
+        assert_eq!(data.keys().collect::<Vec<_>>(), vec![&Profile::Global]);
+
+        let figment = Figment::new().merge(provider);
+        for profile in ["staging", "production"] {
+            let value: String = figment
+                .clone()
+                .select(profile)
+                .extract_inner("api_key")
+                .unwrap();
+            assert_eq!(value, "s3cret");
+        }
+    }
+
+    #[test]
+    fn test_keyring_provider_missing_service() {
+        let config_figment = Figment::from(Serialized::default("keyrings", vec!["user"]));
+        let provider = KeyringProvider::configured_by(config_figment, "api_key");
+
+        let err = provider.extract_config().unwrap_err();
+        assert!(
+            matches!(&err, KeyringError::ConfigError(msg) if msg == "missing required 'service'")
+        );
+
+        let err = provider.data().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "keyring config error: missing required 'service'"
+        );
+    }
+
+    #[test]
+    fn test_keyring_provider_export_snapshot_requires_opt_in() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+        assert!(matches!(
+            provider.export_snapshot(),
+            Err(KeyringError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_snapshot_leaves_secrets_in_place() {
+        let store = mock_store_with(&[("test-app", "join_token", "t0ken")]);
+        let provider = KeyringProvider::new("test-app", "join_token")
+            .with_entry_factory(mock_factory(store.clone()))
+            .consume_once()
+            .allow_snapshot_export();
+        let snapshot = provider.export_snapshot().unwrap();
+        assert_eq!(
+            snapshot["default"].as_dict().unwrap()["join_token"].as_str(),
+            Some("t0ken")
+        );
+        assert!(provider.exists().unwrap());
+
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .allow_snapshot_export();
+        assert!(matches!(
+            provider.export_snapshot(),
+            Err(KeyringError::NotFound(name)) if name == "api_key"
+        ));
+    }
+
+    #[test]
+    fn test_keyring_provider_snapshot_round_trip() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .with_profile(Profile::from("production"))
+            .allow_snapshot_export();
+
+        let snapshot = provider.export_snapshot().unwrap();
+        assert_eq!(
+            snapshot["production"].as_dict().unwrap()["api_key"].as_str(),
+            Some("s3cret")
+        );
+
+        let restored = KeyringProvider::from_snapshot(snapshot);
+        assert_eq!(restored.data().unwrap(), provider.data().unwrap());
+        assert_eq!(restored.metadata().name, "keyring snapshot");
+    }
+
+    #[test]
+    fn test_search_keyrings_returns_matching_keyring() {
+        let user = mock_store_with(&[]);
+        let team = mock_store_with(&[("test-app", "api_key", "team-secret")]);
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user", "team"], false),
+            "api_key",
+        )
+        .with_entry_factory(mock_keyrings_factory(vec![
+            (Keyring::User, user),
+            (Keyring::from("team"), team),
+        ]));
+
+        let config = provider.extract_config().unwrap();
+        let (resolution, secret) = provider.search_keyrings(&config).unwrap().unwrap();
+        assert_eq!(resolution.keyring, Keyring::Named("team".into()));
+        assert_eq!(secret, b"team-secret");
+    }
+
+    #[test]
+    fn test_keyring_provider_as_dotenv() {
+        let store = mock_store_with(&[(
+            "test-app",
+            "env",
+            "# database settings\nDB_HOST=localhost\nDB_USER=\"admin\"\nDB_PASS='p@ss word'\n",
+        )]);
+        let provider = KeyringProvider::new("test-app", "env")
+            .with_entry_factory(mock_factory(store))
+            .as_dotenv();
+
+        let data = provider.data().unwrap();
+        let dict = &data[&Profile::Default];
+        assert_eq!(dict.len(), 3);
+        assert_eq!(dict["DB_HOST"].as_str(), Some("localhost"));
+        assert_eq!(dict["DB_USER"].as_str(), Some("admin"));
+        assert_eq!(dict["DB_PASS"].as_str(), Some("p@ss word"));
+    }
+
+    struct MockAsyncBackend {
+        secrets: Vec<(Keyring, &'static str, &'static str)>,
+    }
+
+    impl AsyncSecretBackend for MockAsyncBackend {
+        async fn get(
+            &self,
+            keyring: &Keyring,
+            _service: &str,
+            username: &str,
+        ) -> error::Result<String> {
+            self.secrets
+                .iter()
+                .find(|(k, name, _)| k == keyring && *name == username)
+                .map(|(_, _, secret)| secret.to_string())
+                .ok_or_else(|| KeyringError::NotFound(username.into()))
+        }
+    }
+
+    #[test]
+    fn test_keyring_provider_data_async() {
+        let backend = MockAsyncBackend {
+            secrets: vec![(Keyring::from("vault"), "api_key", "remote-secret")],
+        };
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user", "vault"], false),
+            "api_key",
+        );
+
+        let data = futures::executor::block_on(provider.data_async(&backend)).unwrap();
+        assert_eq!(
+            data[&Profile::Default]["api_key"].as_str(),
+            Some("remote-secret")
+        );
+
+        let missing = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user"], false),
+            "api_key",
+        );
+        let err = futures::executor::block_on(missing.data_async(&backend)).unwrap_err();
+        assert!(matches!(err, KeyringError::NotFound(_)));
+    }
+
+    #[cfg(feature = "cancellation")]
+    #[test]
+    fn test_data_async_cancellable() {
+        struct HangingBackend;
+
+        impl AsyncSecretBackend for HangingBackend {
+            async fn get(&self, _: &Keyring, _: &str, _: &str) -> error::Result<String> {
+                futures::future::pending().await
+            }
+        }
+
+        let token = tokio_util::sync::CancellationToken::new();
+        let canceller = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            canceller.cancel();
+        });
+
+        let provider = KeyringProvider::new("test-app", "api_key");
+        let start = Instant::now();
+        let err =
+            futures::executor::block_on(provider.data_async_cancellable(&HangingBackend, &token))
+                .unwrap_err();
+        assert!(matches!(err, KeyringError::ServiceUnavailable(msg) if msg == "cancelled"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_invalid_utf8_error() {
+        let err = invalid_utf8_provider(InvalidUtf8::Error)
+            .data()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "secret parse error: secret 'api_key' is not valid UTF-8 (invalid byte at offset 2)"
+        );
+    }
+
+    #[test]
+    fn test_invalid_utf8_error_names_manifest_entry() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        store
+            .build("test-app", "db_password", None)
+            .unwrap()
+            .set_secret(&[b'o', b'k', 0xff])
+            .unwrap();
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            credentials: vec!["api_key".into(), "db_password".into()],
+            ..Default::default()
+        }));
+        let err = KeyringProvider::configured_by(config_figment, "")
+            .with_entry_factory(mock_factory(store))
+            .data()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("secret 'db_password' is not valid UTF-8"));
+    }
+
+    #[test]
+    fn test_invalid_utf8_lossy() {
+        let data = invalid_utf8_provider(InvalidUtf8::Lossy).data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["api_key"].as_str(),
+            Some("ok\u{FFFD}!")
+        );
+    }
+
+    #[test]
+    fn test_invalid_utf8_bytes() {
+        let data = invalid_utf8_provider(InvalidUtf8::Bytes).data().unwrap();
+        let bytes: Vec<u8> = data[&Profile::Default]["api_key"].deserialize().unwrap();
+        assert_eq!(bytes, vec![b'o', b'k', 0xff, b'!']);
+    }
+
+    #[test]
+    fn test_invalid_utf8_default_is_error() {
+        let config: KeyringConfig = Figment::from(Serialized::default("service", "test-app"))
+            .extract()
+            .unwrap();
+        assert_eq!(config.on_invalid_utf8, InvalidUtf8::Error);
+    }
+
+    #[test]
+    fn test_keyring_provider_merged_by_reference() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+
+        let first = Figment::new().merge(&provider);
+        let second = Figment::new()
+            .merge(Serialized::default("port", 8080))
+            .merge(&provider);
+
+        let value: String = first.extract_inner("api_key").unwrap();
+        assert_eq!(value, "s3cret");
+        let value: String = second.extract_inner("api_key").unwrap();
+        assert_eq!(value, "s3cret");
+    }
+
+    fn flagged_provider(flag: Option<bool>, calls: Arc<Mutex<usize>>) -> KeyringProvider {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let mut config_figment = keyrings_figment("test-app", &["user"], false);
+        if let Some(flag) = flag {
+            config_figment = config_figment.merge(Serialized::default("use_keyring", flag));
+        }
+        let inner = mock_factory(store);
+        KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(Arc::new(move |keyring, service, username, modifiers| {
+                *calls.lock().unwrap() += 1;
+                inner(keyring, service, username, modifiers)
+            }))
+            .enabled_if("use_keyring")
+    }
+
+    #[test]
+    fn test_keyring_provider_enabled_if_true() {
+        let calls = Arc::new(Mutex::new(0));
+        let data = flagged_provider(Some(true), calls.clone()).data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("s3cret"));
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_keyring_provider_enabled_if_false() {
+        let calls = Arc::new(Mutex::new(0));
+        let data = flagged_provider(Some(false), calls.clone()).data().unwrap();
+        assert!(data[&Profile::Default].is_empty());
+        assert_eq!(*calls.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_keyring_provider_enabled_if_missing() {
+        let calls = Arc::new(Mutex::new(0));
+        let data = flagged_provider(None, calls.clone()).data().unwrap();
+        assert!(data[&Profile::Default].is_empty());
+        assert_eq!(*calls.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_keyring_provider_credential_overrides() {
+        let user = mock_store_with(&[]);
+        let legacy = mock_store_with(&[("test-app", "api_key", "legacy-secret")]);
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let inner = mock_keyrings_factory(vec![
+            (Keyring::User, user),
+            (Keyring::from("legacy"), legacy),
+        ]);
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user", "legacy"], false),
+            "api_token",
+        )
+        .with_entry_factory(Arc::new(move |keyring, service, username, modifiers| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((keyring.clone(), username.to_string()));
+            inner(keyring, service, username, modifiers)
+        }))
+        .credential_overrides(HashMap::from([(
+            Keyring::from("legacy"),
+            "api_key".to_string(),
+        )]));
+
+        let data = provider.data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["api_token"].as_str(),
+            Some("legacy-secret")
+        );
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                (Keyring::User, "api_token".to_string()),
+                (Keyring::from("legacy"), "api_key".to_string()),
+            ]
+        );
+    }
+
+    fn hashed_provider(expected_sha256: &str) -> KeyringProvider {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            expected_sha256: Some(expected_sha256.into()),
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(mock_factory(store))
+    }
+
+    #[test]
+    fn test_redact_figment_error_scrubs_keyring_values() {
+        let figment = Figment::from(Serialized::default("port", "8080x")).merge(hashed_provider(
+            "1ec1c26b50d5d3c58d9583181af8076655fe00756bf7285940ba3670f99fcba0",
+        ));
+
+        let err = figment.extract_inner::<u32>("api_key").unwrap_err();
+        assert!(err.to_string().contains("s3cret"));
+        let redacted = redact_figment_error(err).to_string();
+        assert!(!redacted.contains("s3cret"));
+        assert!(redacted.contains("[redacted] value"));
+
+        let err = figment.extract_inner::<u32>("port").unwrap_err();
+        assert!(redact_figment_error(err).to_string().contains("8080x"));
+    }
+
+    #[test]
+    fn test_no_prompt_sets_modifier() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let (factory, calls) = recording_factory(store);
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            no_prompt: true,
+            ..Default::default()
+        }));
+        let provider =
+            KeyringProvider::configured_by(config_figment, "api_key").with_entry_factory(factory);
+
+        provider.data().unwrap();
+        let seen: Vec<_> = calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, _, _, modifiers)| {
+                modifiers
+                    .get(keyring_config::backend::NO_PROMPT_MODIFIER)
+                    .cloned()
+            })
+            .collect();
+        assert_eq!(seen, vec![Some("true".to_string())]);
+    }
+
+    #[test]
+    fn test_target_overrides_set_modifier() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let (factory, calls) = recording_factory(store);
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            keyrings: vec![Keyring::User, Keyring::System],
+            optional: true,
+            system_target: Some("/Library/Keychains/Custom.keychain-db".into()),
+            ..Default::default()
+        }));
+        let provider =
+            KeyringProvider::configured_by(config_figment, "missing").with_entry_factory(factory);
+
+        provider.data().unwrap();
+        let seen: Vec<_> = calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(keyring, _, _, modifiers)| (keyring.clone(), modifiers.get("target").cloned()))
+            .collect();
+        assert_eq!(
+            seen,
+            vec![
+                (Keyring::User, None),
+                (
+                    Keyring::System,
+                    Some("/Library/Keychains/Custom.keychain-db".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_target_overrides_reach_search() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            keyrings: vec![Keyring::User, Keyring::System],
+            system_target: Some("/Library/Keychains/Custom.keychain-db".into()),
+            ..Default::default()
+        }));
+        let search = mock_search(store);
+        let provider = KeyringProvider::configured_by(config_figment, "api_key").with_entry_search(
+            Arc::new(move |keyring, service, modifiers| {
+                recorded.lock().unwrap().push((
+                    keyring.clone(),
+                    modifiers.get("target").map(|t| t.to_string()),
+                ));
+                search(keyring, service, modifiers)
+            }),
+        );
+
+        provider.inventory().unwrap();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                (Keyring::User, None),
+                (
+                    Keyring::System,
+                    Some("/Library/Keychains/Custom.keychain-db".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_naming_sets_backend_names() {
+        let store = mock_store_with(&[("gh:github.com", "octocat", "gho_token")]);
+        let (factory, calls) = recording_factory(store);
+        let provider = KeyringProvider::new("github.com", "octocat")
+            .with_naming(Box::new(naming::GhCli))
+            .with_entry_factory(factory);
+
+        let data = provider.data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["octocat"].as_str(),
+            Some("gho_token")
+        );
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![(
+                Keyring::User,
+                "gh:github.com".to_string(),
+                "octocat".to_string(),
+                HashMap::new()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_mirror_to_writes_once() {
+        let system = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let user = mock::Store::new().unwrap();
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["system"], false),
+            "api_key",
+        )
+        .with_entry_factory(mock_keyrings_factory(vec![
+            (Keyring::System, system),
+            (Keyring::User, user.clone()),
+        ]))
+        .mirror_to(Keyring::User);
+
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("s3cret"));
+        let mirrored = user.build("test-app", "api_key", None).unwrap();
+        assert_eq!(mirrored.get_password().unwrap(), "s3cret");
+
+        mirrored.set_password("changed").unwrap();
+        provider.data().unwrap();
+        assert_eq!(mirrored.get_password().unwrap(), "changed");
+    }
+
+    #[test]
+    fn test_mirror_skipped_on_hash_mismatch() {
+        let system = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let user = mock::Store::new().unwrap();
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            keyrings: vec![Keyring::System],
+            expected_sha256: Some(
+                "0000000000000000000000000000000000000000000000000000000000000000".into(),
+            ),
+            ..Default::default()
+        }));
+        let provider = KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(mock_keyrings_factory(vec![
+                (Keyring::System, system),
+                (Keyring::User, user.clone()),
+            ]))
+            .mirror_to(Keyring::User);
+
+        assert_eq!(
+            provider.data().unwrap_err().to_string(),
+            "backend error: secret integrity check failed"
+        );
+        let mirrored = user.build("test-app", "api_key", None).unwrap();
+        assert!(matches!(
+            mirrored.get_password(),
+            Err(keyring_core::Error::NoEntry)
+        ));
+    }
+
+    #[test]
+    fn test_mirror_failure_does_not_fail_read() {
+        let system = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["system"], false),
+            "api_key",
+        )
+        .with_entry_factory(mock_keyrings_factory(vec![(Keyring::System, system)]))
+        .mirror_to(Keyring::User);
+
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_secret_present() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+        assert_eq!(provider.secret_bytes().unwrap(), Some(b"s3cret".to_vec()));
+    }
+
+    #[test]
+    fn test_apply_to_sets_field() {
+        #[derive(Default)]
+        struct Config {
+            api_key: String,
+        }
+
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+
+        let mut config = Config::default();
+        assert!(provider.apply_to(|secret| config.api_key = secret).unwrap());
+        assert_eq!(config.api_key, "s3cret");
+    }
+
+    #[test]
+    fn test_secret_optional_missing() {
+        let store = mock::Store::new().unwrap();
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user"], true),
+            "api_key",
+        )
+        .with_entry_factory(mock_factory(store.clone()));
+        assert_eq!(provider.secret().unwrap(), None);
+
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+        assert!(matches!(
+            provider.secret(),
+            Err(KeyringError::NotFound(name)) if name == "api_key"
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_privileges() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            keyrings: vec![Keyring::System],
+            check_privileges: true,
+            ..Default::default()
+        }));
+        let mut provider = KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(mock_factory(store));
+
+        provider.privilege_check = || Ok(false);
+        assert!(matches!(
+            provider.secret(),
+            Err(KeyringError::PermissionDenied(msg))
+                if msg == "system keyring requires elevated privileges"
+        ));
+
+        provider.privilege_check = || Ok(true);
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+
+        provider.privilege_check = || {
+            Err(KeyringError::Unsupported(
+                "check_privileges is not supported".into(),
+            ))
+        };
+        assert!(matches!(
+            provider.secret(),
+            Err(KeyringError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_profile_selector_reads_config() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        // `APP_PROFILE=staging`, as merged in by `Env::prefixed("APP_")`.
+        let config_figment = keyrings_figment("test-app", &["user"], false)
+            .merge(Serialized::default("profile", "staging"));
+        let provider = KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(mock_factory(store))
+            .with_profile(Profile::from("production"))
+            .profile_selector(|figment| {
+                figment
+                    .extract_inner::<String>("profile")
+                    .map(Profile::from)
+                    .unwrap_or_default()
+            });
+
+        let data = provider.data().unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(
+            data[&Profile::from("staging")]["api_key"].as_str(),
+            Some("s3cret")
+        );
+    }
+
+    #[cfg(feature = "generate")]
+    #[test]
+    fn test_generate_and_store() {
+        let store = mock::Store::new().unwrap();
+        let provider =
+            KeyringProvider::new("test-app", "session_key").with_entry_factory(mock_factory(store));
+
+        let secret = provider.generate_and_store(32).unwrap();
+        assert_eq!(secret.len(), 32);
+        assert!(secret.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_eq!(provider.secret().unwrap(), Some(secret));
+    }
+
+    #[cfg(feature = "generate")]
+    #[test]
+    fn test_generate_and_store_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let generate = || {
+            KeyringProvider::new("test-app", "session_key")
+                .with_entry_factory(mock_factory(mock::Store::new().unwrap()))
+                .with_rng(StdRng::seed_from_u64(42))
+        };
+        let provider = generate();
+        let secret = provider.generate_and_store(16).unwrap();
+        assert_eq!(provider.secret().unwrap(), Some(secret.clone()));
+        assert_eq!(generate().generate_and_store(16).unwrap(), secret);
+        assert_ne!(provider.generate_and_store(16).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_also_default() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .with_profile(Profile::from("production"))
+            .also_default();
+
+        let data = provider.data().unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(
+            data[&Profile::from("production")]["api_key"].as_str(),
+            Some("s3cret")
+        );
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_named_store() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        keyring_config::backend::register_store("named-store-test", store);
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            store: Some("named-store-test".into()),
+            ..Default::default()
+        }));
+        let provider = KeyringProvider::configured_by(config_figment, "api_key");
+
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_concurrent_store_initialization() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        keyring_config::backend::register_store("concurrent-store-test", store);
+
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    let store = match i % 3 {
+                        0 => Some("concurrent-store-test".to_string()),
+                        1 => Some("no-such-concurrent-store".to_string()),
+                        _ => None,
+                    };
+                    let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+                        service: "test-app".into(),
+                        store: store.clone(),
+                        ..Default::default()
+                    }));
+                    let provider = KeyringProvider::configured_by(config_figment, "api_key");
+                    (store, provider.exists())
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            match thread.join().expect("provider initialization panicked") {
+                (Some(name), result) if name == "concurrent-store-test" => {
+                    assert!(result.unwrap())
+                }
+                (Some(_), result) => {
+                    assert!(matches!(result, Err(KeyringError::ConfigError(_))))
+                }
+                // The native store may be unavailable here, but must not
+                // report a replaced default store.
+                (None, result) => assert!(!matches!(
+                    result,
+                    Err(KeyringError::ServiceUnavailable(msg)) if msg.contains("replaced")
+                )),
+            }
+        }
+    }
+
+    #[test]
+    fn test_named_store_leaves_default_store() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        keyring_config::backend::register_store("mixed-store-test", store);
+        let provider = |store: Option<&str>| {
+            let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+                service: "test-app".into(),
+                store: store.map(String::from),
+                ..Default::default()
+            }));
+            KeyringProvider::configured_by(config_figment, "api_key")
+        };
+        provider(None).exists().ok();
+        let default = keyring_core::get_default_store();
+
+        let threads: Vec<_> = (0..12)
+            .map(|i| {
+                std::thread::spawn(move || match i % 3 {
+                    0 => assert_eq!(
+                        provider(Some("mixed-store-test")).secret().unwrap(),
+                        Some("s3cret".into())
+                    ),
+                    // A store keyring knows by name, but nobody registered.
+                    1 => assert!(matches!(
+                        provider(Some("sample")).exists(),
+                        Err(KeyringError::ConfigError(_))
+                    )),
+                    _ => assert!(!matches!(
+                        provider(None).exists(),
+                        Err(KeyringError::ServiceUnavailable(msg)) if msg.contains("replaced")
+                    )),
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().expect("store lookup panicked");
+        }
+
+        let current = keyring_core::get_default_store();
+        match (default, current) {
+            (Some(default), Some(current)) => assert!(Arc::ptr_eq(&default, &current)),
+            (default, current) => assert_eq!(default.is_none(), current.is_none()),
+        }
+    }
+
+    #[test]
+    fn test_unknown_store() {
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            store: Some("no-such-store".into()),
+            ..Default::default()
+        }));
+        let provider = KeyringProvider::configured_by(config_figment, "api_key");
+
+        assert!(matches!(
+            provider.secret(),
+            Err(KeyringError::ConfigError(msg)) if msg == "unknown credential store 'no-such-store'"
+        ));
+    }
+
+    #[test]
+    fn test_versioned_follows_pointer() {
+        let store = mock_store_with(&[
+            ("test-app", "api_key", "bare"),
+            ("test-app", "api_key.v3", "old"),
+            ("test-app", "api_key.v4", "current"),
+            ("test-app", "api_key.v5", "staged"),
+            ("test-app", "api_key.current", "v4"),
+        ]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .versioned("current");
+
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("current"));
+        assert_eq!(
+            provider.resolve().unwrap().unwrap().credential,
+            "api_key.v4"
+        );
+    }
+
+    #[test]
+    fn test_versioned_without_pointer_uses_bare_name() {
+        let store = mock_store_with(&[("test-app", "api_key", "bare")]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .versioned("current");
+
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("bare"));
+    }
+
+    #[test]
+    fn test_diagnose_records_durations() {
+        let user = mock::Store::new().unwrap();
+        let system = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let inner = mock_keyrings_factory(vec![(Keyring::User, user), (Keyring::System, system)]);
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user", "system"], false),
+            "api_key",
+        )
+        .with_entry_factory(Arc::new(move |keyring, service, username, modifiers| {
+            if *keyring == Keyring::User {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            inner(keyring, service, username, modifiers)
+        }));
+
+        let timings = provider.diagnose().unwrap();
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings[0].0, Keyring::User);
+        assert!(timings[0].1 >= Duration::from_millis(20));
+        assert!(matches!(timings[0].2, Err(KeyringError::NotFound(_))));
+        assert_eq!(timings[1].0, Keyring::System);
+        assert!(timings[1].2.is_ok());
+    }
+
+    #[test]
+    fn test_append_to_array() {
+        let store = mock_store_with(&[("test-app", "admin_token", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "admin_token")
+            .with_entry_factory(mock_factory(store))
+            .append_to_array("allowed_tokens");
+
+        let figment = Figment::from(Serialized::default(
+            "allowed_tokens",
+            vec!["public", "internal"],
+        ));
+        let tokens: Vec<String> = figment
+            .clone()
+            .admerge(&provider)
+            .extract_inner("allowed_tokens")
+            .unwrap();
+        assert_eq!(tokens, vec!["public", "internal", "s3cret"]);
+
+        let tokens: Vec<String> = figment
+            .merge(&provider)
+            .extract_inner("allowed_tokens")
+            .unwrap();
+        assert_eq!(tokens, vec!["s3cret"]);
+    }
+
+    #[test]
+    fn test_with_encoding() {
+        let store = mock_store_with(&[
+            ("test-app", "hex_key", "733363726574"),
+            ("test-app", "b64_key", "czNjcmV0"),
+        ]);
+        let provider = KeyringProvider::new("test-app", "hex_key")
+            .with_entry_factory(mock_factory(store.clone()))
+            .with_encoding(Encoding::Hex);
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["hex_key"].as_str(), Some("s3cret"));
+
+        let provider = KeyringProvider::new("test-app", "b64_key")
+            .with_entry_factory(mock_factory(store))
+            .with_encoding(Encoding::Auto);
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_data_shape_leaves_consumed_secret() {
+        let store = mock_store_with(&[("test-app", "join_token", "t0ken")]);
+        let provider = KeyringProvider::new("test-app", "join_token")
+            .with_entry_factory(mock_factory(store))
+            .consume_once();
+
+        let shape = provider.data_shape().unwrap();
+        assert_eq!(
+            shape[&Profile::Default]["join_token"].as_str(),
+            Some("[redacted]")
+        );
+        assert!(provider.exists().unwrap());
+        let data = provider.data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["join_token"].as_str(),
+            Some("t0ken")
+        );
+    }
+
+    #[test]
+    fn test_data_shape_matches_data() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .with_profile(Profile::from("production"))
+            .namespaced_by_service();
+
+        let data = provider.data().unwrap();
+        let shape = provider.data_shape().unwrap();
+        assert_eq!(
+            shape.keys().collect::<Vec<_>>(),
+            data.keys().collect::<Vec<_>>()
+        );
+        let profile = Profile::from("production");
+        let service = shape[&profile]["test-app"].as_dict().unwrap();
+        assert_eq!(
+            service.keys().collect::<Vec<_>>(),
+            data[&profile]["test-app"]
+                .as_dict()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(service["api_key"].as_str(), Some("[redacted]"));
+    }
+
+    #[test]
+    fn test_configured_by_arc_shares_figment() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let config_figment = Arc::new(keyrings_figment("test-app", &["user"], false));
+        let provider = KeyringProvider::configured_by_arc(config_figment.clone(), "api_key")
+            .with_entry_factory(mock_factory(store));
+
+        assert!(Arc::ptr_eq(&provider.config_figment, &config_figment));
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_self_test_reports_each_keyring() {
+        let user = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user", "system"], false),
+            "api_key",
+        )
+        .with_entry_factory(mock_keyrings_factory(vec![(Keyring::User, user)]));
+
+        let report = provider.self_test().unwrap();
+        assert!(!report.all_reachable());
+        assert_eq!(report.keyrings.len(), 2);
+        assert_eq!(report.keyrings[0].keyring, Keyring::User);
+        assert!(report.keyrings[0].error.is_none());
+        assert_eq!(report.keyrings[1].keyring, Keyring::System);
+        assert!(matches!(
+            report.keyrings[1].error,
+            Some(KeyringError::ServiceUnavailable(_))
+        ));
+    }
+
+    #[test]
+    fn test_strip_trailing_newline() {
+        let store = mock_store_with(&[
+            ("test-app", "crlf", "s3cret\r\n"),
+            ("test-app", "lf", "s3cret\n\n"),
+            ("test-app", "spaces", " s3cret  "),
+        ]);
+        let secret = |name: &str| {
+            KeyringProvider::new("test-app", name)
+                .with_entry_factory(mock_factory(store.clone()))
+                .strip_trailing_newline()
+                .secret()
+                .unwrap()
+                .unwrap()
+        };
+
+        assert_eq!(secret("crlf"), "s3cret");
+        assert_eq!(secret("lf"), "s3cret\n");
+        assert_eq!(secret("spaces"), " s3cret  ");
+    }
+
+    struct FakeClock(Mutex<Instant>);
+
+    impl FakeClock {
+        fn advance(&self, by: Duration) {
+            *self.0.lock().unwrap() += by;
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_cache_refetches_after_ttl() {
+        let store = mock_store_with(&[("test-app", "api_key", "first")]);
+        let clock = Arc::new(FakeClock(Mutex::new(Instant::now())));
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store.clone()))
+            .cache_for(Duration::from_secs(60))
+            .with_clock(clock.clone());
+
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("first"));
+        store
+            .build("test-app", "api_key", None)
+            .unwrap()
+            .set_password("second")
+            .unwrap();
+
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("first"));
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("second"));
+    }
+
+    #[test]
+    fn test_reload_reports_change() {
+        let store = mock_store_with(&[("test-app", "api_key", "first")]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store.clone()))
+            .cache_for(Duration::from_secs(60));
+
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("first"));
+        assert!(!provider.reload().unwrap());
+
+        store
+            .build("test-app", "api_key", None)
+            .unwrap()
+            .set_password("second")
+            .unwrap();
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("first"));
+        assert!(provider.reload().unwrap());
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("second"));
+        assert!(!provider.reload().unwrap());
+    }
+
+    #[test]
+    fn test_reload_rejects_multiple_sources() {
+        assert!(matches!(
+            manifest_provider(&["api_key", "db_password"]).reload(),
+            Err(KeyringError::Unsupported(_))
+        ));
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store.clone()))
+            .merge_json_parts(vec!["part1"]);
+        assert!(matches!(
+            provider.reload(),
+            Err(KeyringError::Unsupported(_))
+        ));
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .fallback_chain(vec![
+                FallbackSource::Keyring,
+                FallbackSource::Literal("fallback".into()),
+            ]);
+        assert!(matches!(
+            provider.reload(),
+            Err(KeyringError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_negative_cache() {
+        let calls = Arc::new(Mutex::new(0));
+        let counter = calls.clone();
+        let store = mock::Store::new().unwrap();
+        let inner = mock_factory(store);
+        let clock = Arc::new(FakeClock(Mutex::new(Instant::now())));
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user"], true),
+            "api_key",
+        )
+        .with_entry_factory(Arc::new(move |keyring, service, username, modifiers| {
+            *counter.lock().unwrap() += 1;
+            inner(keyring, service, username, modifiers)
+        }))
+        .with_negative_cache(Duration::from_secs(5))
+        .with_clock(clock.clone());
+
+        assert_eq!(provider.secret().unwrap(), None);
+        assert_eq!(provider.secret().unwrap(), None);
+        assert_eq!(*calls.lock().unwrap(), 1);
+
+        clock.advance(Duration::from_secs(6));
+        assert_eq!(provider.secret().unwrap(), None);
+        assert_eq!(*calls.lock().unwrap(), 2);
+
+        provider.store("s3cret").unwrap();
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_prefetch_fills_cache() {
+        let calls = Arc::new(Mutex::new(0));
+        let counter = calls.clone();
+        let inner = mock_factory(mock_store_with(&[
+            ("test-app", "api_key", "key-secret"),
+            ("test-app", "db_password", "db-secret"),
+        ]));
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            credentials: vec!["api_key".into(), "db_password".into()],
+            ..Default::default()
+        }));
+        let provider = KeyringProvider::configured_by(config_figment, "").with_entry_factory(
+            Arc::new(move |keyring, service, username, modifiers| {
+                *counter.lock().unwrap() += 1;
+                inner(keyring, service, username, modifiers)
+            }),
+        );
+
+        provider.prefetch().unwrap();
+        assert_eq!(*calls.lock().unwrap(), 2);
+
+        let data = provider.data().unwrap();
+        assert_eq!(*calls.lock().unwrap(), 2);
+        let dict = &data[&Profile::Default];
+        assert_eq!(dict["api_key"].as_str(), Some("key-secret"));
+        assert_eq!(dict["db_password"].as_str(), Some("db-secret"));
+    }
+
+    #[test]
+    fn test_prefetch_follows_chain_and_hash() {
+        let calls = Arc::new(Mutex::new(0));
+        let counter = calls.clone();
+        let inner = mock_factory(mock_store_with(&[("test-app", "api_key", "s3cret")]));
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(Arc::new(move |keyring, service, username, modifiers| {
+                *counter.lock().unwrap() += 1;
+                inner(keyring, service, username, modifiers)
+            }))
+            .fallback_chain(vec![
+                FallbackSource::Literal("literal".into()),
+                FallbackSource::Keyring,
+            ]);
+        provider.prefetch().unwrap();
+        assert_eq!(*calls.lock().unwrap(), 0);
+
+        let provider = hashed_provider(&"0".repeat(64));
+        assert!(matches!(
+            provider.prefetch(),
+            Err(KeyringError::BackendError(msg)) if msg == "secret integrity check failed"
+        ));
+        assert!(provider.cache.lock().unwrap().is_empty());
+    }
+
+    fn manifest_provider(credentials: &[&str]) -> KeyringProvider {
+        let store = mock_store_with(&[
+            ("test-app", "api_key", "key-secret"),
+            ("test-app", "db_password", "db-secret"),
+            ("test-app", "broken", "unreadable"),
+        ]);
+        let entry = store.build("test-app", "broken", None).unwrap();
+        let cred: &mock::Cred = entry.as_any().downcast_ref().unwrap();
+        cred.set_error(keyring_core::Error::PlatformFailure("broken".into()));
+
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            credentials: credentials.iter().map(|name| (*name).into()).collect(),
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "").with_entry_factory(mock_factory(store))
+    }
+
+    #[test]
+    fn test_manifest_data() {
+        let data = manifest_provider(&["api_key", "db_password"])
+            .data()
+            .unwrap();
+        let dict = &data[&Profile::Default];
+        assert_eq!(dict["api_key"].as_str(), Some("key-secret"));
+        assert_eq!(dict["db_password"].as_str(), Some("db-secret"));
+    }
+
+    #[test]
+    fn test_manifest_per_entry_optional() {
+        let store = mock_store_with(&[("test-app", "api_key", "key-secret")]);
+        let config_figment = Figment::from(Serialized::defaults(serde_json::json!({
+            "service": "test-app",
+            "credentials": ["api_key", { "name": "metrics_token", "optional": true }],
+        })));
+        let provider = KeyringProvider::configured_by(config_figment, "")
+            .with_entry_factory(mock_factory(store));
+
+        let data = provider.data().unwrap();
+        let dict = &data[&Profile::Default];
+        assert_eq!(dict["api_key"].as_str(), Some("key-secret"));
+        assert!(!dict.contains_key("metrics_token"));
+
+        let err = manifest_provider(&["api_key", "metrics_token"])
+            .data()
+            .unwrap_err();
+        assert!(err.to_string().contains("metrics_token"));
+    }
+
+    #[test]
+    fn test_load_all_reporting_partial_success() {
+        let provider = manifest_provider(&["api_key", "broken", "db_password"]);
+
+        let (loaded, failures) = provider.load_all_reporting();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded["api_key"].as_str(), Some("key-secret"));
+        assert_eq!(loaded["db_password"].as_str(), Some("db-secret"));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "broken");
+        assert!(matches!(failures[0].1, KeyringError::BackendError(_)));
+    }
+
+    #[test]
+    fn test_name_case() {
+        for (name_case, expected) in [
+            (NameCase::Preserve, "Api_Key"),
+            (NameCase::Lower, "api_key"),
+            (NameCase::Upper, "API_KEY"),
+        ] {
+            let store = mock_store_with(&[("test-app", expected, "s3cret")]);
+            let (factory, calls) = recording_factory(store);
+            let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+                service: "test-app".into(),
+                name_case,
+                ..Default::default()
+            }));
+            let provider = KeyringProvider::configured_by(config_figment, "Api_Key")
+                .with_entry_factory(factory);
+
+            let data = provider.data().unwrap();
+            assert_eq!(data[&Profile::Default]["Api_Key"].as_str(), Some("s3cret"));
+            let usernames: Vec<_> = calls
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(_, _, username, _)| username.clone())
+                .collect();
+            assert_eq!(usernames, vec![expected.to_string()]);
+        }
+    }
+
+    /// A credential that keeps arbitrary attributes, which the mock store
+    /// does not support.
+    #[derive(Default)]
+    struct AttributedCred {
+        secret: Mutex<Option<Vec<u8>>>,
+        attributes: Mutex<HashMap<String, String>>,
+    }
+
+    impl keyring_core::api::CredentialApi for AttributedCred {
+        fn set_secret(&self, secret: &[u8]) -> keyring_core::Result<()> {
+            *self.secret.lock().unwrap() = Some(secret.to_vec());
+            Ok(())
+        }
+
+        fn get_secret(&self) -> keyring_core::Result<Vec<u8>> {
+            self.secret
+                .lock()
+                .unwrap()
+                .clone()
+                .ok_or(keyring_core::Error::NoEntry)
+        }
+
+        fn get_attributes(&self) -> keyring_core::Result<HashMap<String, String>> {
+            self.get_secret()?;
+            Ok(self.attributes.lock().unwrap().clone())
+        }
+
+        fn update_attributes(&self, attributes: &HashMap<&str, &str>) -> keyring_core::Result<()> {
+            self.get_secret()?;
+            let mut stored = self.attributes.lock().unwrap();
+            for (key, value) in attributes {
+                stored.insert(key.to_string(), value.to_string());
+            }
+            Ok(())
+        }
+
+        fn delete_credential(&self) -> keyring_core::Result<()> {
+            self.secret
+                .lock()
+                .unwrap()
+                .take()
+                .map(|_| ())
+                .ok_or(keyring_core::Error::NoEntry)
+        }
+
+        fn get_credential(&self) -> keyring_core::Result<Option<Arc<keyring_core::Credential>>> {
+            Ok(None)
+        }
+
+        fn get_specifiers(&self) -> Option<(String, String)> {
+            None
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_on_ambiguous() {
+        let candidate = |secret: &str, modified: &str| {
+            let cred = AttributedCred::default();
+            *cred.secret.lock().unwrap() = Some(secret.as_bytes().to_vec());
+            cred.attributes
+                .lock()
+                .unwrap()
+                .insert("modified".into(), modified.into());
+            keyring_core::Entry::new_with_credential(Arc::new(cred))
+        };
+        let store = mock::Store::new().unwrap();
+        let factory: EntryFactory = Arc::new(move |_keyring, service, username, _modifiers| {
+            let entry = store.build(service, username, None).unwrap();
+            let cred: &mock::Cred = entry.as_any().downcast_ref().unwrap();
+            cred.set_error(keyring_core::Error::Ambiguous(vec![
+                candidate("old", "2024-06-01"),
+                candidate("new", "2025-01-15"),
+                candidate("older", "2023-12-31"),
+            ]));
+            Ok(entry)
+        });
+        let provider = |policy: OnAmbiguous| {
+            let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+                service: "test-app".into(),
+                on_ambiguous: policy,
+                ..Default::default()
+            }));
+            KeyringProvider::configured_by(config_figment, "api_key")
+                .with_entry_factory(factory.clone())
+        };
+
+        assert!(matches!(
+            provider(OnAmbiguous::Error).secret(),
+            Err(KeyringError::BackendError(msg)) if msg == "entry is ambiguous: 3 credentials match"
+        ));
+        assert_eq!(
+            provider(OnAmbiguous::First).secret().unwrap().as_deref(),
+            Some("old")
+        );
+        assert_eq!(
+            provider(OnAmbiguous::Newest).secret().unwrap().as_deref(),
+            Some("new")
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        struct SlowCred;
+
+        impl keyring_core::api::CredentialApi for SlowCred {
+            fn set_secret(&self, _secret: &[u8]) -> keyring_core::Result<()> {
+                Ok(())
+            }
+
+            fn get_secret(&self) -> keyring_core::Result<Vec<u8>> {
+                std::thread::sleep(Duration::from_millis(500));
+                Ok(b"s3cret".to_vec())
+            }
+
+            fn delete_credential(&self) -> keyring_core::Result<()> {
+                Ok(())
+            }
+
+            fn get_credential(
+                &self,
+            ) -> keyring_core::Result<Option<Arc<keyring_core::Credential>>> {
+                Ok(None)
+            }
+
+            fn get_specifiers(&self) -> Option<(String, String)> {
+                None
+            }
+
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+        }
+
+        let attempts = Arc::new(Mutex::new(0));
+        let counter = attempts.clone();
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(Arc::new(
+                move |_keyring, _service, _username, _modifiers| {
+                    *counter.lock().unwrap() += 1;
+                    Ok(keyring_core::Entry::new_with_credential(Arc::new(SlowCred)))
+                },
+            ))
+            .with_timeout(Duration::from_millis(20));
+
+        assert!(matches!(
+            provider.secret(),
+            Err(KeyringError::Timeout(waited)) if waited == Duration::from_millis(20)
+        ));
+        assert_eq!(*attempts.lock().unwrap(), 2);
+
+        let provider = provider.with_timeout(Duration::from_secs(5));
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_timed_reads_share_a_thread() {
+        struct ThreadCred(Arc<Mutex<Vec<std::thread::ThreadId>>>);
+
+        impl keyring_core::api::CredentialApi for ThreadCred {
+            fn set_secret(&self, _secret: &[u8]) -> keyring_core::Result<()> {
+                Ok(())
+            }
+
+            fn get_secret(&self) -> keyring_core::Result<Vec<u8>> {
+                self.0.lock().unwrap().push(std::thread::current().id());
+                Ok(b"s3cret".to_vec())
+            }
+
+            fn delete_credential(&self) -> keyring_core::Result<()> {
+                Ok(())
+            }
+
+            fn get_credential(
+                &self,
+            ) -> keyring_core::Result<Option<Arc<keyring_core::Credential>>> {
+                Ok(None)
+            }
+
+            fn get_specifiers(&self) -> Option<(String, String)> {
+                None
+            }
+
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+        }
+
+        let threads = Arc::new(Mutex::new(Vec::new()));
+        let cred: Arc<keyring_core::Credential> = Arc::new(ThreadCred(threads.clone()));
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(Arc::new(
+                move |_keyring, _service, _username, _modifiers| {
+                    Ok(keyring_core::Entry::new_with_credential(cred.clone()))
+                },
+            ))
+            .with_timeout(Duration::from_secs(5));
+
+        for _ in 0..3 {
+            assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+        }
+        let threads = threads.lock().unwrap();
+        assert_eq!(threads.len(), 3);
+        assert!(threads.iter().all(|id| *id == threads[0]));
+        assert_ne!(threads[0], std::thread::current().id());
+    }
+
+    #[test]
+    fn test_timed_read_not_queued_behind_slow_read() {
+        struct FirstSlowCred(AtomicUsize, std::sync::mpsc::SyncSender<()>);
+
+        impl keyring_core::api::CredentialApi for FirstSlowCred {
+            fn set_secret(&self, _secret: &[u8]) -> keyring_core::Result<()> {
+                Ok(())
+            }
+
+            fn get_secret(&self) -> keyring_core::Result<Vec<u8>> {
+                if self.0.fetch_add(1, Ordering::SeqCst) == 0 {
+                    self.1.send(()).ok();
+                    std::thread::sleep(Duration::from_millis(500));
+                }
+                Ok(b"s3cret".to_vec())
+            }
+
+            fn delete_credential(&self) -> keyring_core::Result<()> {
+                Ok(())
+            }
+
+            fn get_credential(
+                &self,
+            ) -> keyring_core::Result<Option<Arc<keyring_core::Credential>>> {
+                Ok(None)
+            }
+
+            fn get_specifiers(&self) -> Option<(String, String)> {
+                None
+            }
+
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+        }
+
+        let (started, slow_started) = std::sync::mpsc::sync_channel(1);
+        let cred: Arc<keyring_core::Credential> =
+            Arc::new(FirstSlowCred(AtomicUsize::new(0), started));
+        let provider = KeyringProvider::new("test-app", "api_key").with_entry_factory(Arc::new(
+            move |_keyring, _service, _username, _modifiers| {
+                Ok(keyring_core::Entry::new_with_credential(cred.clone()))
+            },
+        ));
+
+        let slow = provider.clone().with_timeout(Duration::from_secs(5));
+        let slow = std::thread::spawn(move || slow.secret());
+        slow_started.recv().unwrap();
+
+        // The second read gets its own worker instead of timing out (and
+        // retrying) behind the first.
+        let fast = provider.with_timeout(Duration::from_millis(200));
+        let start = Instant::now();
+        assert_eq!(fast.secret().unwrap().as_deref(), Some("s3cret"));
+        assert!(start.elapsed() < Duration::from_millis(200));
+        assert_eq!(slow.join().unwrap().unwrap().as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_store_with_attributes() {
+        let cred: Arc<keyring_core::Credential> = Arc::new(AttributedCred::default());
+        let provider = KeyringProvider::new("test-app", "api_key").with_entry_factory(Arc::new(
+            move |_keyring, _service, _username, _modifiers| {
+                Ok(keyring_core::Entry::new_with_credential(cred.clone()))
+            },
+        ));
+        assert_eq!(provider.attributes().unwrap(), None);
+
+        let attributes = HashMap::from([
+            ("created_by".to_string(), "deploy".to_string()),
+            ("rotated_at".to_string(), "2024-05-01".to_string()),
+        ]);
+        provider
+            .store_with_attributes("s3cret", attributes.clone())
+            .unwrap();
+
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+        assert_eq!(provider.attributes().unwrap(), Some(attributes));
+    }
+
+    #[test]
+    fn test_store_with_attributes_unsupported() {
+        let store = mock::Store::new().unwrap();
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+
+        let attributes = HashMap::from([("created_by".to_string(), "deploy".to_string())]);
+        assert!(matches!(
+            provider.store_with_attributes("s3cret", attributes),
+            Err(KeyringError::Unsupported(_))
+        ));
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_circuit_breaker_skips_failing_keyring() {
+        let user = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let inner = mock_keyrings_factory(vec![(Keyring::User, user)]);
+        let system_calls = Arc::new(Mutex::new(0));
+        let counted = system_calls.clone();
+        let clock = Arc::new(FakeClock(Mutex::new(Instant::now())));
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["system", "user"], true),
+            "api_key",
+        )
+        .with_entry_factory(Arc::new(move |keyring, service, username, modifiers| {
+            if *keyring == Keyring::System {
+                *counted.lock().unwrap() += 1;
+            }
+            inner(keyring, service, username, modifiers)
+        }))
+        .with_circuit_breaker(2, Duration::from_secs(30))
+        .with_clock(clock.clone());
+
+        for _ in 0..4 {
+            assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+        }
+        assert_eq!(*system_calls.lock().unwrap(), 2);
+
+        clock.advance(Duration::from_secs(29));
+        provider.secret().unwrap();
+        assert_eq!(*system_calls.lock().unwrap(), 2);
+
+        clock.advance(Duration::from_secs(2));
+        provider.secret().unwrap();
+        assert_eq!(*system_calls.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_from_env() {
+        // SAFETY: these variables are unique to this test and no other code
+        // reads the environment concurrently.
+        unsafe {
+            std::env::set_var("FROM_ENV_TEST_SERVICE", "test-app");
+            std::env::set_var("FROM_ENV_TEST_KEYRINGS", "system, team");
+            std::env::set_var("FROM_ENV_TEST_OPTIONAL", "true");
+            std::env::set_var("FROM_ENV_TEST_CREDENTIAL", "api_key");
+        }
+
+        let provider = KeyringProvider::from_env("FROM_ENV_TEST").unwrap();
+        let config = provider.extract_config().unwrap();
+        assert_eq!(provider.credential_name, "api_key");
+        assert_eq!(config.service, "test-app");
+        assert_eq!(
+            config.keyrings,
+            vec![Keyring::System, Keyring::from("team")]
+        );
+        assert!(config.optional);
+
+        assert!(matches!(
+            KeyringProvider::from_env("FROM_ENV_UNSET"),
+            Err(KeyringError::ConfigError(msg)) if msg == "FROM_ENV_UNSET_SERVICE is not set"
+        ));
+    }
+
+    #[test]
+    fn test_strip_bom() {
+        let store = mock_store_with(&[("test-app", "api_key", "\u{feff}s3cret")]);
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+
+        assert_eq!(
+            provider.secret().unwrap().as_deref(),
+            Some("\u{feff}s3cret")
+        );
+        assert_eq!(
+            provider.strip_bom().secret().unwrap().as_deref(),
+            Some("s3cret")
+        );
+    }
+
+    #[test]
+    fn test_as_list() {
+        let store = mock_store_with(&[(
+            "test-app",
+            "allowed_origins",
+            " https://a.example,https://b.example ,, https://c.example\n",
+        )]);
+        let provider = KeyringProvider::new("test-app", "allowed_origins")
+            .with_entry_factory(mock_factory(store))
+            .as_list(',');
+
+        let origins: Vec<String> = Figment::from(&provider)
+            .extract_inner("allowed_origins")
+            .unwrap();
+        assert_eq!(
+            origins,
+            vec![
+                "https://a.example",
+                "https://b.example",
+                "https://c.example"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_separator_default_nests_on_dot() {
+        let store = mock_store_with(&[("test-app", "db.password", "s3cret")]);
+        let provider =
+            KeyringProvider::new("test-app", "db.password").with_entry_factory(mock_factory(store));
+
+        let data = provider.data().unwrap();
+        let db = data[&Profile::Default]["db"].as_dict().unwrap();
+        assert_eq!(db["password"].as_str(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_key_separator_custom() {
+        let store = mock_store_with(&[("test-app", "api.example.com/token", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "api.example.com/token")
+            .with_entry_factory(mock_factory(store))
+            .with_key_separator(Some("/"));
+
+        let data = provider.data().unwrap();
+        let host = data[&Profile::Default]["api.example.com"]
+            .as_dict()
+            .unwrap();
+        assert_eq!(host["token"].as_str(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_key_separator_leaves_content_keys() {
+        let store = mock_store_with(&[("test-app", "tokens", r#"{"example.com": "tok"}"#)]);
+        let provider = KeyringProvider::new("test-app", "")
+            .with_entry_factory(mock_factory(store))
+            .merge_json_parts(vec!["tokens"]);
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["example.com"].as_str(), Some("tok"));
+
+        let store = mock_store_with(&[("test-app", "env", "DB.USER=admin\n")]);
+        let provider = KeyringProvider::new("test-app", "env")
+            .with_entry_factory(mock_factory(store))
+            .as_dotenv();
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["DB.USER"].as_str(), Some("admin"));
+    }
+
+    #[test]
+    fn test_key_separator_disabled() {
+        let store = mock_store_with(&[("test-app", "db.password", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "db.password")
+            .with_entry_factory(mock_factory(store))
+            .with_key_separator(None);
+
+        let data = provider.data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["db.password"].as_str(),
+            Some("s3cret")
+        );
+    }
+
+    #[test]
+    fn test_resolve_references_nested() {
+        let store = mock_store_with(&[("test-app", "db_password", "s3cret")]);
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+        let figment = Figment::from(Serialized::defaults(serde_json::json!({
+            "database": {
+                "host": "localhost",
+                "password": "keyring:db_password",
+            },
+        })));
+
+        let resolved = provider.resolve_references(figment).unwrap();
+        assert_eq!(
+            resolved
+                .extract_inner::<String>("database.password")
+                .unwrap(),
+            "s3cret"
+        );
+        assert_eq!(
+            resolved.extract_inner::<String>("database.host").unwrap(),
+            "localhost"
+        );
+    }
+
+    #[test]
+    fn test_resolve_references_missing() {
+        let store = mock::Store::new().unwrap();
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+        let figment = Figment::from(Serialized::default("password", "keyring:nope"));
+
+        assert!(matches!(
+            provider.resolve_references(figment),
+            Err(KeyringError::NotFound(name)) if name == "nope"
+        ));
+    }
+
+    fn flaky_provider(patterns: &[&str]) -> KeyringProvider {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let entry = store.build("test-app", "api_key", None).unwrap();
+        let cred: &mock::Cred = entry.as_any().downcast_ref().unwrap();
+        cred.set_error(keyring_core::Error::PlatformFailure(
+            "resource temporarily busy".into(),
+        ));
+
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            transient_error_patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(mock_factory(store))
+    }
+
+    #[test]
+    fn test_transient_error_pattern_retries() {
+        assert!(flaky_provider(&[]).secret().is_err());
+        assert_eq!(
+            flaky_provider(&["temporarily busy"])
+                .secret()
+                .unwrap()
+                .as_deref(),
+            Some("s3cret")
+        );
+    }
+
+    #[test]
+    fn test_memory_keyring_round_trip() {
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("memory-round-trip", &["memory"], false),
+            "api_key",
+        );
+        assert!(matches!(
+            provider.secret(),
+            Err(KeyringError::NotFound(name)) if name == "api_key"
+        ));
+
+        provider.store("s3cret").unwrap();
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("s3cret"));
+
+        provider.delete().unwrap();
+        assert!(provider.secret().is_err());
+    }
+
+    #[test]
+    fn test_memory_keyring_search_order() {
+        let config_figment = keyrings_figment("memory-search-order", &["team", "memory"], true);
+        let provider = KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(Arc::new(
+                |keyring, service, username, modifiers| match keyring {
+                    Keyring::Memory => keyring_config::backend::create_entry_in(
+                        None, keyring, service, username, modifiers,
+                    ),
+                    _ => Err(KeyringError::NotFound(username.into())),
+                },
+            ));
+        assert_eq!(provider.secret().unwrap(), None);
+
+        KeyringProvider::configured_by(
+            keyrings_figment("memory-search-order", &["memory"], false),
+            "api_key",
+        )
+        .store("s3cret")
+        .unwrap();
+        let resolution = provider.resolve().unwrap().unwrap();
+        assert_eq!(resolution.keyring, Keyring::Memory);
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_descriptor_serializes() {
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            keyrings: vec![Keyring::User, Keyring::from("team")],
+            optional: true,
+            ..Default::default()
+        }));
+        let provider = KeyringProvider::configured_by(config_figment, "api_key")
+            .as_key("credentials.api")
+            .with_profile(Profile::new("prod"));
+
+        let json = serde_json::to_value(provider.descriptor().unwrap()).unwrap();
+        assert_eq!(json["service"], "test-app");
+        assert_eq!(json["keyrings"], serde_json::json!(["user", "team"]));
+        assert_eq!(json["credential"], "api_key");
+        assert_eq!(json["config_key"], "credentials.api");
+        assert_eq!(json["profile"], "prod");
+        assert_eq!(json["optional"], true);
+        assert_eq!(json["read_only"], false);
+        assert_eq!(json["encoding"], "raw");
+    }
+
+    /// Config source that fails its first `failures` reads.
+    #[derive(Clone)]
+    struct FlakyConfig {
+        failures: usize,
+        reads: Arc<Mutex<usize>>,
+    }
+
+    impl Provider for FlakyConfig {
+        fn metadata(&self) -> Metadata {
+            Metadata::named("flaky config")
+        }
+
+        fn data(&self) -> std::result::Result<Map<Profile, Dict>, Error> {
+            let mut reads = self.reads.lock().unwrap();
+            *reads += 1;
+            if *reads <= self.failures {
+                return Err(Error::from("config source unavailable".to_string()));
+            }
+            Serialized::default("service", "test-app").data()
+        }
+    }
+
+    fn flaky_config_provider(failures: usize, attempts: u32) -> KeyringProvider {
+        let source = FlakyConfig {
+            failures,
+            reads: Arc::new(Mutex::new(0)),
+        };
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = KeyringProvider::configured_by(Figment::from(source.clone()), "api_key")
+            .with_entry_factory(mock_factory(store));
+        match attempts {
+            0 => provider,
+            attempts => provider.retry_config(attempts, move || Figment::from(source.clone())),
+        }
+    }
+
+    #[test]
+    fn test_retry_config_recovers() {
+        assert!(matches!(
+            flaky_config_provider(1, 0).secret(),
+            Err(KeyringError::ConfigError(msg)) if msg.contains("config source unavailable")
+        ));
+        assert_eq!(
+            flaky_config_provider(1, 1).secret().unwrap().as_deref(),
+            Some("s3cret")
+        );
+    }
+
+    #[test]
+    fn test_retry_config_gives_up() {
+        assert!(matches!(
+            flaky_config_provider(3, 2).secret(),
+            Err(KeyringError::ConfigError(_))
+        ));
+    }
+
+    // figment's Error is large, but it is what the hook returns.
+    #[allow(clippy::result_large_err)]
+    #[test]
+    fn test_map_error_recovers_not_found() {
+        let store = mock::Store::new().unwrap();
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .map_error(|e| match e {
+                KeyringError::NotFound(_) => Ok(None),
+                e => Err(e.to_string().into()),
+            });
+
+        let data = provider.data().unwrap();
+        assert!(data[&Profile::Default].is_empty());
+
+        let figment = Figment::from(Serialized::default("api_key", "fallback")).merge(provider);
+        assert_eq!(
+            figment.extract_inner::<String>("api_key").unwrap(),
+            "fallback"
+        );
+    }
+
+    #[allow(clippy::result_large_err)]
+    #[test]
+    fn test_map_error_replaces_and_fails() {
+        let store = mock::Store::new().unwrap();
+        let replaced = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store.clone()))
+            .map_error(|_| Ok(Some(Value::from("placeholder"))));
+        assert_eq!(
+            replaced.data().unwrap()[&Profile::Default]["api_key"].as_str(),
+            Some("placeholder")
+        );
+
+        let failing = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .map_error(|_| Err(Error::from("custom failure".to_string())));
+        assert_eq!(failing.data().unwrap_err().to_string(), "custom failure");
+    }
+
+    #[allow(clippy::result_large_err)]
+    #[test]
+    fn test_map_error_value_is_reshaped() {
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(mock::Store::new().unwrap()))
+            .with_profile(Profile::from("production"))
+            .namespaced_by_service()
+            .also_default()
+            .map_error(|_| Ok(Some(Value::from("placeholder"))));
+
+        let data = provider.data().unwrap();
+        for profile in [Profile::from("production"), Profile::Default] {
+            let service = data[&profile]["test-app"].as_dict().unwrap();
+            assert_eq!(service["api_key"].as_str(), Some("placeholder"));
+        }
+    }
+
+    #[test]
+    fn test_inventory_merges_keyrings() {
+        let user = mock_store_with(&[
+            ("test-app", "api_key", "a"),
+            ("test-app", "db_password", "b"),
+            ("other-app", "api_key", "c"),
+        ]);
+        let team = mock_store_with(&[
+            ("test-app", "api_key", "d"),
+            ("test-app", "deploy_token", "e"),
+        ]);
+        let stores = HashMap::from([(Keyring::User, user), (Keyring::from("team"), team)]);
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user", "team", "vault"], false),
+            "api_key",
+        )
+        .with_entry_search(Arc::new(move |keyring, service, modifiers| {
+            let Some(store) = stores.get(keyring) else {
+                return Err(KeyringError::Unsupported("no enumeration".into()));
+            };
+            mock_search(store.clone())(keyring, service, modifiers)
+        }));
+
+        assert_eq!(
+            provider.inventory().unwrap(),
+            vec![
+                ("api_key".into(), vec![Keyring::User, Keyring::from("team")]),
+                ("db_password".into(), vec![Keyring::User]),
+                ("deploy_token".into(), vec![Keyring::from("team")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_values_tagged_with_keyring_metadata() {
+        let store = mock_store_with(&[("test-app", "db.password", "s3cret")]);
+        let provider =
+            KeyringProvider::new("test-app", "db.password").with_entry_factory(mock_factory(store));
+        let figment = Figment::from(Serialized::default("db.host", "localhost")).merge(provider);
+
+        let metadata = figment.find_metadata("db.password").unwrap();
+        assert_eq!(metadata.name, "keyring");
+        assert_eq!(metadata.source, Some(sensitive_source()));
+        assert_ne!(figment.find_metadata("db.host").unwrap().name, "keyring");
+
+        let value = figment.find_value("db.password").unwrap();
+        assert_eq!(figment.get_metadata(value.tag()), Some(metadata));
+    }
+
+    #[test]
+    fn test_max_secret_bytes() {
+        let provider = |max| {
+            let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+            let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+                service: "test-app".into(),
+                max_secret_bytes: Some(max),
+                ..Default::default()
+            }));
+            KeyringProvider::configured_by(config_figment, "api_key")
+                .with_entry_factory(mock_factory(store))
+        };
+
+        assert_eq!(provider(6).secret().unwrap().as_deref(), Some("s3cret"));
+        assert!(matches!(
+            provider(5).secret(),
+            Err(KeyringError::BackendError(msg)) if msg == "secret exceeds max length"
+        ));
+    }
+
+    #[test]
+    fn test_before_access_fires_per_keyring() {
+        let user = mock::Store::new().unwrap();
+        let team = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user", "team"], false),
+            "api_key",
+        )
+        .with_entry_factory(mock_keyrings_factory(vec![
+            (Keyring::User, user),
+            (Keyring::from("team"), team),
+        ]))
+        .before_access(move |keyring, service, username| {
+            recorded.lock().unwrap().push((
+                keyring.clone(),
+                service.to_string(),
+                username.to_string(),
+            ));
+        });
+
+        provider.data().unwrap();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                (Keyring::User, "test-app".into(), "api_key".into()),
+                (Keyring::from("team"), "test-app".into(), "api_key".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_consume_once() {
+        let store = mock_store_with(&[("test-app", "join_token", "t0ken")]);
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            optional: true,
+            ..Default::default()
+        }));
+        let provider = KeyringProvider::configured_by(config_figment, "join_token")
+            .with_entry_factory(mock_factory(store))
+            .consume_once();
+
+        let first = provider.data().unwrap();
+        assert_eq!(
+            first[&Profile::Default]["join_token"].as_str(),
+            Some("t0ken")
+        );
+        let second = provider.data().unwrap();
+        assert!(second[&Profile::Default].is_empty());
+    }
+
+    fn coerced_provider(secret: &str, coerce: ValueKind) -> KeyringProvider {
+        let store = mock_store_with(&[("test-app", "setting", secret)]);
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            coerce: Some(coerce),
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "setting")
+            .with_entry_factory(mock_factory(store))
+    }
+
+    #[test]
+    fn test_coerce_integer() {
+        let figment = Figment::from(coerced_provider("8443\n", ValueKind::Integer));
+        assert_eq!(figment.extract_inner::<u16>("setting").unwrap(), 8443);
+
+        assert!(matches!(
+            coerced_provider("84x", ValueKind::Integer).load(),
+            Err(KeyringError::ParseError(msg)) if msg == "secret 'setting' is not a valid integer"
+        ));
+    }
+
+    #[test]
+    fn test_coerce_bool() {
+        let figment = Figment::from(coerced_provider("True", ValueKind::Bool));
+        assert!(figment.extract_inner::<bool>("setting").unwrap());
+
+        let config_figment = Figment::from(Serialized::defaults(serde_json::json!({
+            "service": "test-app",
+            "coerce": "boolean",
+        })));
+        let config = KeyringConfig::from_figment(&config_figment).unwrap();
+        assert_eq!(config.coerce, Some(ValueKind::Bool));
+    }
+
+    #[test]
+    fn test_include_source_key() {
+        let user = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(user))
+            .include_source_key();
+
+        let data = provider.data().unwrap();
+        let dict = &data[&Profile::Default];
+        assert_eq!(dict["api_key"].as_str(), Some("s3cret"));
+        assert_eq!(dict["api_key_source"].as_str(), Some("user"));
+    }
+
+    #[test]
+    fn test_migrate_between_keyrings() {
+        let system = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let user = mock::Store::new().unwrap();
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user", "system"], false),
+            "api_key",
+        )
+        .with_entry_factory(mock_keyrings_factory(vec![
+            (Keyring::User, user.clone()),
+            (Keyring::System, system),
+        ]));
+
+        assert!(provider.migrate(Keyring::System, Keyring::User).unwrap());
+        let entry = user.build("test-app", "api_key", None).unwrap();
+        assert_eq!(entry.get_password().unwrap(), "s3cret");
+        assert!(!provider.migrate(Keyring::System, Keyring::User).unwrap());
+
+        let cred: &mock::Cred = entry.as_any().downcast_ref().unwrap();
+        cred.set_error(keyring_core::Error::PlatformFailure("broken".into()));
+        assert!(matches!(
+            provider.migrate(Keyring::System, Keyring::User),
+            Err(KeyringError::BackendError(msg)) if msg.starts_with("writing to user keyring")
+        ));
+    }
+
+    #[test]
+    fn test_fallback_chain_dev() {
+        // SAFETY: the variable is unique to this test and no other code
+        // reads the environment concurrently.
+        unsafe {
+            std::env::set_var("FALLBACK_CHAIN_DEV_API_KEY", "from-env");
+        }
+        let file = std::env::temp_dir().join("figment-keyring-fallback-chain-dev");
+        std::fs::remove_file(&file).ok();
+
+        let store = mock_store_with(&[("test-app", "api_key", "from-keyring")]);
+        let chain = vec![
+            FallbackSource::File(file.clone()),
+            FallbackSource::Env("FALLBACK_CHAIN_DEV_API_KEY".into()),
+            FallbackSource::Keyring,
+        ];
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user"], false),
+            "api_key",
+        )
+        .with_entry_factory(mock_factory(store))
+        .fallback_chain(chain);
+        let figment = Figment::from(&provider);
+        assert_eq!(
+            figment.extract_inner::<String>("api_key").unwrap(),
+            "from-env"
+        );
+
+        std::fs::write(&file, "from-file").unwrap();
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("from-file"));
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn test_fallback_chain_prod() {
+        // SAFETY: the variable is unique to this test and no other code
+        // reads the environment concurrently.
+        unsafe {
+            std::env::set_var("FALLBACK_CHAIN_PROD_API_KEY", "from-env");
+        }
+        let store = mock::Store::new().unwrap();
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user"], false),
+            "api_key",
+        )
+        .with_entry_factory(mock_factory(store.clone()))
+        .fallback_chain(vec![FallbackSource::Keyring]);
+        assert!(matches!(
+            provider.secret(),
+            Err(KeyringError::NotFound(name)) if name == "api_key"
+        ));
+
+        store
+            .build("test-app", "api_key", None)
+            .unwrap()
+            .set_password("from-keyring")
+            .unwrap();
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("from-keyring"));
+
+        let provider = provider.fallback_chain(vec![
+            FallbackSource::Env("FALLBACK_CHAIN_PROD_UNSET".into()),
+            FallbackSource::Literal("default".into()),
+            FallbackSource::Keyring,
+        ]);
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("default"));
+    }
+
+    #[test]
+    fn test_as_tenant_profiles() {
+        let store = mock_store_with(&[(
+            "test-app",
+            "tenant_keys",
+            r#"{"acme": "acme-secret", "globex": "globex-secret"}"#,
+        )]);
+        let provider = KeyringProvider::new("test-app", "tenant_keys")
+            .with_entry_factory(mock_factory(store))
+            .as_tenant_profiles("api_key");
+
+        let data = provider.data().unwrap();
+        assert_eq!(data.len(), 2);
+
+        let figment = Figment::new().merge(&provider);
+        let acme: String = figment
+            .clone()
+            .select("acme")
+            .extract_inner("api_key")
+            .unwrap();
+        let globex: String = figment.select("globex").extract_inner("api_key").unwrap();
+        assert_eq!(acme, "acme-secret");
+        assert_eq!(globex, "globex-secret");
+    }
+
+    #[test]
+    fn test_as_tenant_profiles_follows_chain_and_hash() {
+        let tenants = r#"{"acme": "acme-secret"}"#;
+        let provider = KeyringProvider::new("test-app", "tenant_keys")
+            .with_entry_factory(mock_factory(mock::Store::new().unwrap()))
+            .fallback_chain(vec![
+                FallbackSource::Keyring,
+                FallbackSource::Literal(tenants.into()),
+            ])
+            .as_tenant_profiles("api_key");
+        let data = provider.data().unwrap();
+        assert_eq!(
+            data[&Profile::from("acme")]["api_key"].as_str(),
+            Some("acme-secret")
+        );
+
+        let store = mock_store_with(&[("test-app", "tenant_keys", tenants)]);
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            expected_sha256: Some(
+                "0000000000000000000000000000000000000000000000000000000000000000".into(),
+            ),
+            ..Default::default()
+        }));
+        let provider = KeyringProvider::configured_by(config_figment, "tenant_keys")
+            .with_entry_factory(mock_factory(store.clone()))
+            .consume_once()
+            .as_tenant_profiles("api_key");
+        assert_eq!(
+            provider.data().unwrap_err().to_string(),
+            "backend error: secret integrity check failed"
+        );
+        assert!(provider.exists().unwrap());
+    }
+
+    #[test]
+    fn test_strict_errors_missing_is_ok() {
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user"], true),
+            "api_key",
+        )
+        .with_entry_factory(mock_factory(mock::Store::new().unwrap()))
+        .strict_errors();
+        assert_eq!(provider.secret().unwrap(), None);
+        assert!(provider.data().unwrap()[&Profile::Default].is_empty());
+    }
+
+    #[test]
+    fn test_strict_errors_propagates_denied() {
+        let factory: EntryFactory =
+            Arc::new(|_, _, _, _| Err(KeyringError::PermissionDenied("locked".into())));
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user"], true),
+            "api_key",
+        )
+        .with_entry_factory(factory);
+        assert_eq!(provider.secret().unwrap(), None);
+
+        let provider = provider.strict_errors();
+        assert!(matches!(
+            provider.secret(),
+            Err(KeyringError::PermissionDenied(msg)) if msg == "locked"
+        ));
+    }
+
+    #[test]
+    fn test_swap_service_username() {
+        let store = mock_store_with(&[("api_key", "test-app", "s3cret")]);
+        let (factory, calls) = recording_factory(store);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(factory)
+            .swap_service_username();
+
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![(
+                Keyring::User,
+                "api_key".to_string(),
+                "test-app".to_string(),
+                HashMap::new()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_numeric_looking_secret_stays_string() {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            pin: String,
+            exponent: String,
+        }
+
+        let store =
+            mock_store_with(&[("test-app", "pin", "0123"), ("test-app", "exponent", "1e5")]);
+        let provider = |name: &str| {
+            KeyringProvider::new("test-app", name).with_entry_factory(mock_factory(store.clone()))
+        };
+        let data = provider("pin").data().unwrap();
+        assert!(matches!(&data[&Profile::Default]["pin"], Value::String(_, s) if s == "0123"));
+
+        let figment = Figment::new()
+            .merge(provider("pin"))
+            .merge(provider("exponent"));
+        let config: Config = figment.extract().unwrap();
+        assert_eq!(config.pin, "0123");
+        assert_eq!(config.exponent, "1e5");
+        let config: Config = figment.extract_lossy().unwrap();
+        assert_eq!(config.pin, "0123");
+        assert_eq!(config.exponent, "1e5");
+    }
+
+    #[test]
+    fn test_as_credential() {
+        let store = mock_store_with(&[("test-app", "svc-account", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "database")
+            .credential_overrides(HashMap::from([(Keyring::User, "svc-account".into())]))
+            .with_entry_factory(mock_factory(store))
+            .as_credential();
+
+        let figment = Figment::new().merge(provider);
+        let username: String = figment.extract_inner("database.username").unwrap();
+        let password: String = figment.extract_inner("database.password").unwrap();
+        assert_eq!(username, "svc-account");
+        assert_eq!(password, "s3cret");
+    }
+
+    #[test]
+    fn test_with_context_reaches_modifiers() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let (factory, calls) = recording_factory(store);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(factory)
+            .with_context("req-42");
+
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("s3cret"));
+        let seen: Vec<_> = calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, _, _, modifiers)| modifiers.get("access-reason").cloned())
+            .collect();
+        assert_eq!(seen, vec![Some("req-42".to_string())]);
+    }
+
+    #[test]
+    fn test_into_merge() {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+        let base = || Figment::from(Serialized::default("api_key", "from-file"));
+
+        let figment = provider.clone().into_merge(base());
+        assert_eq!(
+            figment.extract_inner::<String>("api_key").unwrap(),
+            "s3cret"
+        );
+
+        let figment = provider
+            .precedence_hint(Precedence::Fallback)
+            .into_merge(base());
+        assert_eq!(
+            figment.extract_inner::<String>("api_key").unwrap(),
+            "from-file"
+        );
+    }
+
+    #[test]
+    fn test_into_admerge() {
+        let store = mock_store_with(&[("test-app", "admin_token", "s3cret")]);
+        let provider = KeyringProvider::new("test-app", "admin_token")
+            .with_entry_factory(mock_factory(store))
+            .append_to_array("tokens");
+        let base = || Figment::from(Serialized::default("tokens", vec!["public"]));
+
+        let figment = provider.clone().into_admerge(base());
+        assert_eq!(
+            figment.extract_inner::<Vec<String>>("tokens").unwrap(),
+            vec!["public", "s3cret"]
+        );
+        let figment = provider.clone().into_merge(base());
+        assert_eq!(
+            figment.extract_inner::<Vec<String>>("tokens").unwrap(),
+            vec!["s3cret"]
+        );
+
+        let figment = provider
+            .precedence_hint(Precedence::Fallback)
+            .into_admerge(base());
+        assert_eq!(
+            figment.extract_inner::<Vec<String>>("tokens").unwrap(),
+            vec!["public", "s3cret"]
+        );
+    }
+
+    #[test]
+    fn test_lazy_fetches_on_first_use() {
+        let calls = Arc::new(Mutex::new(0));
+        let counter = calls.clone();
+        let inner = mock_factory(mock_store_with(&[("test-app", "api_key", "s3cret")]));
+        let lazy = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(Arc::new(move |keyring, service, username, modifiers| {
+                *counter.lock().unwrap() += 1;
+                inner(keyring, service, username, modifiers)
+            }))
+            .lazy();
+
+        assert!(!lazy.is_fetched());
+        assert_eq!(*calls.lock().unwrap(), 0);
+
+        assert_eq!(lazy.get().unwrap(), Some("s3cret"));
+        assert_eq!(lazy.get().unwrap(), Some("s3cret"));
+        assert!(lazy.is_fetched());
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_name_fallbacks() {
+        let user = mock_store_with(&[("test-app", "legacy_key", "user-secret")]);
+        let system = mock_store_with(&[("test-app", "api_key", "system-secret")]);
+        let provider = KeyringProvider::configured_by(
+            keyrings_figment("test-app", &["user", "system"], false),
+            "api_key",
+        )
+        .with_entry_factory(mock_keyrings_factory(vec![
+            (Keyring::User, user),
+            (Keyring::System, system),
+        ]))
+        .with_name_fallbacks(vec!["old_key", "legacy_key"]);
+
+        assert_eq!(provider.secret().unwrap().as_deref(), Some("user-secret"));
+        assert_eq!(
+            provider.resolve().unwrap(),
+            Some(Resolution {
+                keyring: Keyring::User,
+                credential: "legacy_key".into(),
+                fallback: true,
+            })
+        );
+        let data = provider.data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["api_key"].as_str(),
+            Some("user-secret")
+        );
+    }
+
+    #[test]
+    fn test_from_entries() {
+        let store = mock_store_with(&[
+            ("billing", "stripe_key", "sk-billing"),
+            ("mailer", "smtp_password", "smtp-secret"),
+        ]);
+        let spec = |service: &str, credential: &str, config_key: Option<&str>| EntrySpec {
+            keyring: Keyring::User,
+            service: service.into(),
+            credential: credential.into(),
+            config_key: config_key.map(String::from),
+            optional: false,
+        };
+        let provider = KeyringProvider::from_entries(vec![
+            spec("billing", "stripe_key", Some("payments.api_key")),
+            spec("mailer", "smtp_password", None),
+            EntrySpec {
+                optional: true,
+                ..spec("mailer", "missing", None)
+            },
+        ])
+        .with_entry_factory(mock_factory(store));
+
+        let figment = Figment::new().merge(provider);
+        let api_key: String = figment.extract_inner("payments.api_key").unwrap();
+        let smtp: String = figment.extract_inner("smtp_password").unwrap();
+        assert_eq!(api_key, "sk-billing");
+        assert_eq!(smtp, "smtp-secret");
+        assert!(figment.find_value("missing").is_err());
+    }
+
+    #[test]
+    fn test_secrets_from_config() {
+        let store = mock_store_with(&[
+            ("billing", "stripe_key", "sk-billing"),
+            ("mailer", "smtp_password", "unreadable"),
+        ]);
+        let entry = store.build("mailer", "smtp_password", None).unwrap();
+        let cred: &mock::Cred = entry.as_any().downcast_ref().unwrap();
+        // Mock errors only last for the next read.
+        let break_entry = || cred.set_error(keyring_core::Error::PlatformFailure("broken".into()));
+        let config_figment = Figment::from(Serialized::defaults(serde_json::json!({
+            "service": "unused",
+            "secrets": [
+                {
+                    "keyring": "user",
+                    "service": "billing",
+                    "credential": "stripe_key",
+                    "config_key": "payments.api_key",
+                },
+                {
+                    "keyring": "user",
+                    "service": "mailer",
+                    "credential": "smtp_password",
+                    "optional": true,
+                },
+            ],
+        })));
+        let provider = KeyringProvider::configured_by(config_figment, "")
+            .with_entry_factory(mock_factory(store));
+
+        break_entry();
+        let figment = Figment::new().merge(&provider);
+        let api_key: String = figment.extract_inner("payments.api_key").unwrap();
+        assert_eq!(api_key, "sk-billing");
+        assert!(figment.find_value("smtp_password").is_err());
+        assert!(matches!(
+            provider.prefetch(),
+            Err(KeyringError::Unsupported(_))
+        ));
+
+        break_entry();
+        let strict = provider.strict_errors();
+        assert!(strict.data().unwrap_err().to_string().contains("broken"));
+    }
+
+    fn replicated_provider(strategy: ReadStrategy) -> KeyringProvider {
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            keyrings: vec![Keyring::User, Keyring::System, Keyring::from("team")],
+            read_strategy: strategy,
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "api_key").with_entry_factory(
+            mock_keyrings_factory(vec![
+                (
+                    Keyring::User,
+                    mock_store_with(&[("test-app", "api_key", "user")]),
+                ),
+                (
+                    Keyring::System,
+                    mock_store_with(&[("test-app", "api_key", "system")]),
+                ),
+                (
+                    Keyring::from("team"),
+                    mock_store_with(&[("test-app", "api_key", "team")]),
+                ),
+            ]),
+        )
+    }
+
+    fn reads(provider: &KeyringProvider, count: usize) -> Vec<String> {
+        (0..count)
+            .map(|_| provider.secret().unwrap().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_read_strategy_first_wins() {
+        let provider = replicated_provider(ReadStrategy::FirstWins);
+        assert_eq!(reads(&provider, 3), vec!["user", "user", "user"]);
+    }
+
+    #[test]
+    fn test_read_strategy_round_robin() {
+        let provider = replicated_provider(ReadStrategy::RoundRobin);
+        assert_eq!(reads(&provider, 4), vec!["user", "system", "team", "user"]);
+    }
+
+    #[test]
+    fn test_read_strategy_random_is_seeded() {
+        let first = reads(
+            &replicated_provider(ReadStrategy::Random).with_read_seed(7),
+            12,
+        );
+        let second = reads(
+            &replicated_provider(ReadStrategy::Random).with_read_seed(7),
+            12,
+        );
+        assert_eq!(first, second);
+        for keyring in ["user", "system", "team"] {
+            assert!(first.iter().any(|read| read == keyring));
+        }
+
+        let mut state = 7;
+        let mut order = ["user", "system", "team"];
+        for i in (1..order.len()).rev() {
+            let j = next_random(&mut state) as usize % (i + 1);
+            order.swap(i, j);
+        }
+        assert_eq!(first[0], order[0]);
+    }
+
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn test_help_hint_in_missing_error() {
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(mock::Store::new().unwrap()))
+            .help("run `myapp login`");
+        let error = Figment::new()
+            .merge(provider.clone())
+            .extract_inner::<String>("api_key")
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("secret 'api_key' not found in any keyring (help: run `myapp login`)"));
+
+        let provider = provider.map_error(|e| Err(format!("custom: {}", e).into()));
+        let error = Figment::new()
+            .merge(provider)
+            .extract_inner::<String>("api_key")
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("custom: secret not found: api_key (help: run `myapp login`)"));
+    }
+
+    #[test]
+    #[cfg(feature = "encoding")]
+    fn test_with_charset_utf16le() {
+        let store = mock::Store::new().unwrap();
+        let utf16: Vec<u8> = "pässwörd"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        store
+            .build("test-app", "api_key", None)
+            .unwrap()
+            .set_secret(&utf16)
+            .unwrap();
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store))
+            .with_charset(Charset::UTF_16LE);
+        let value: String = Figment::new()
+            .merge(provider)
+            .extract_inner("api_key")
+            .unwrap();
+        assert_eq!(value, "pässwörd");
+    }
+
+    #[test]
+    #[cfg(feature = "encoding")]
+    fn test_with_charset_strips_trailing_newline() {
+        let store = mock::Store::new().unwrap();
+        let provider = |charset, stored: &[u8]| {
+            store
+                .build("test-app", "api_key", None)
+                .unwrap()
+                .set_secret(stored)
+                .unwrap();
+            KeyringProvider::new("test-app", "api_key")
+                .with_entry_factory(mock_factory(store.clone()))
+                .with_charset(charset)
+                .strip_trailing_newline()
+        };
+
+        let value = provider(Charset::UTF_16BE, &[0x00, 0x61, 0x00, 0x62, 0x00, 0x0A]).secret();
+        assert_eq!(value.unwrap().as_deref(), Some("ab"));
+        let value = provider(Charset::UTF_16LE, &[0x61, 0x00, 0x62, 0x00, 0x0A, 0x00]).secret();
+        assert_eq!(value.unwrap().as_deref(), Some("ab"));
+    }
+
+    #[test]
+    fn test_or_falls_back_to_other_provider() {
+        let store = mock_store_with(&[("team-app", "api_key", "team-secret")]);
+        let provider = KeyringProvider::new("personal-app", "api_key")
+            .with_entry_factory(mock_factory(store.clone()))
+            .or(KeyringProvider::new("team-app", "api_key")
+                .with_entry_factory(mock_factory(store.clone())));
+        let value: String = Figment::new()
+            .merge(provider)
+            .extract_inner("api_key")
+            .unwrap();
+        assert_eq!(value, "team-secret");
+
+        let provider = KeyringProvider::new("team-app", "api_key")
+            .with_entry_factory(mock_factory(store.clone()))
+            .or(KeyringProvider::new("personal-app", "api_key")
+                .with_entry_factory(mock_factory(store)));
+        let data = provider.data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["api_key"].as_str(),
+            Some("team-secret")
+        );
+    }
+
+    #[test]
+    fn test_or_reaches_data_shape_and_snapshot() {
+        let store = mock_store_with(&[("team-app", "api_key", "team-secret")]);
+        let provider = KeyringProvider::new("personal-app", "api_key")
+            .with_entry_factory(mock_factory(store.clone()))
+            .allow_snapshot_export()
+            .or(KeyringProvider::new("team-app", "api_key")
+                .with_entry_factory(mock_factory(store))
+                .consume_once());
+
+        let shape = provider.data_shape().unwrap();
+        assert!(shape[&Profile::Default].contains_key("api_key"));
+        assert_ne!(
+            shape[&Profile::Default]["api_key"].as_str(),
+            Some("team-secret")
+        );
+        let snapshot = provider.export_snapshot().unwrap();
+        assert_eq!(
+            snapshot["default"].as_dict().unwrap()["api_key"].as_str(),
+            Some("team-secret")
+        );
+        let data = provider.data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["api_key"].as_str(),
+            Some("team-secret")
+        );
+    }
+
+    #[test]
+    fn test_or_skipped_when_disabled() {
+        let calls = Arc::new(Mutex::new(0));
+        let store = mock_store_with(&[("team-app", "api_key", "team-secret")]);
+        let provider = flagged_provider(Some(false), Arc::new(Mutex::new(0))).or(
+            KeyringProvider::new("team-app", "api_key").with_entry_factory({
+                let calls = calls.clone();
+                let inner = mock_factory(store);
+                Arc::new(move |keyring, service, username, modifiers| {
+                    *calls.lock().unwrap() += 1;
+                    inner(keyring, service, username, modifiers)
+                })
+            }),
+        );
+        let data = provider.data().unwrap();
+        assert!(data[&Profile::Default].is_empty());
+        assert_eq!(*calls.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_expected_sha256_match() {
+        let provider =
+            hashed_provider("1ec1c26b50d5d3c58d9583181af8076655fe00756bf7285940ba3670f99fcba0");
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_expected_sha256_rejected_for_many_credentials() {
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            credentials: vec!["api_key".into()],
+            expected_sha256: Some("0000".into()),
+            ..Default::default()
+        }));
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = KeyringProvider::configured_by(config_figment, "")
+            .with_entry_factory(mock_factory(store.clone()));
+        assert!(matches!(
+            provider.load(),
+            Err(KeyringError::ConfigError(msg))
+                if msg == "expected_sha256 only applies to a single credential"
+        ));
+
+        let provider =
+            hashed_provider("1ec1c26b50d5d3c58d9583181af8076655fe00756bf7285940ba3670f99fcba0")
+                .expand_profiles(vec!["dev", "prod"]);
+        assert!(matches!(provider.load(), Err(KeyringError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_expected_sha256_mismatch() {
+        let provider =
+            hashed_provider("0000000000000000000000000000000000000000000000000000000000000000");
+        let err = provider.data().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "backend error: secret integrity check failed"
+        );
+    }
+
+    #[test]
+    fn test_keyring_provider_set_if_absent() {
+        let store = mock_store_with(&[]);
+        let provider =
+            KeyringProvider::new("test-app", "api_key").with_entry_factory(mock_factory(store));
+
+        assert!(!provider.exists().unwrap());
+        assert!(provider.set_if_absent("first").unwrap());
+        assert!(provider.exists().unwrap());
+
+        assert!(!provider.set_if_absent("second").unwrap());
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("first"));
+    }
+
+    #[test]
+    fn test_keyring_provider_map_config() {
+        let store = mock_store_with(&[("test-app-eu", "api_key", "eu-secret")]);
+        let (factory, calls) = recording_factory(store);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(factory)
+            .map_config(|mut config| {
+                config.service.push_str("-eu");
+                config
+            });
+
+        let data = provider.data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["api_key"].as_str(),
+            Some("eu-secret")
+        );
+        let services: Vec<_> = calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, service, _, _)| service.clone())
+            .collect();
+        assert_eq!(services, vec!["test-app-eu".to_string()]);
+    }
+
+    fn strict_provider(failing: Keyring) -> KeyringProvider {
+        let stores: Vec<(Keyring, Arc<mock::Store>)> = vec![
+            (Keyring::User, mock::Store::new().unwrap()),
+            (Keyring::System, mock::Store::new().unwrap()),
+            (
+                Keyring::from("backup"),
+                mock_store_with(&[("test-app", "api_key", "backup-secret")]),
+            ),
+        ];
+        let (_, store) = stores.iter().find(|(k, _)| *k == failing).unwrap();
+        let entry = store.build("test-app", "api_key", None).unwrap();
+        let cred: &mock::Cred = entry.as_any().downcast_ref().unwrap();
+        cred.set_error(keyring_core::Error::PlatformFailure("broken".into()));
+
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            keyrings: stores.iter().map(|(k, _)| k.clone()).collect(),
+            optional: true,
+            primary_strict: true,
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(mock_keyrings_factory(stores))
+    }
+
+    fn drift_provider(system_secret: &str) -> KeyringProvider {
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            keyrings: vec![Keyring::User, Keyring::System],
+            detect_drift: true,
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "api_key").with_entry_factory(
+            mock_keyrings_factory(vec![
+                (
+                    Keyring::User,
+                    mock_store_with(&[("test-app", "api_key", "s3cret")]),
+                ),
+                (
+                    Keyring::System,
+                    mock_store_with(&[("test-app", "api_key", system_secret)]),
+                ),
+            ]),
+        )
+    }
+
+    #[test]
+    fn test_detect_drift() {
+        assert!(matches!(
+            drift_provider("stale").secret(),
+            Err(KeyringError::BackendError(msg))
+                if msg.starts_with("value drift detected across keyrings")
+        ));
+        assert_eq!(
+            drift_provider("s3cret").secret().unwrap(),
+            Some("s3cret".into())
+        );
+    }
+
+    #[test]
+    fn test_primary_strict_aborts_on_primary_error() {
+        let err = strict_provider(Keyring::User).data().unwrap_err();
+        assert!(err.to_string().contains("broken"));
+    }
+
+    #[test]
+    fn test_primary_strict_skips_secondary_error() {
+        let data = strict_provider(Keyring::System).data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["api_key"].as_str(),
+            Some("backup-secret")
+        );
+    }
+
+    fn denied_provider(continue_on_denied: bool) -> KeyringProvider {
+        let user = mock_store_with(&[("test-app", "api_key", "user-secret")]);
+        let entry = user.build("test-app", "api_key", None).unwrap();
+        let cred: &mock::Cred = entry.as_any().downcast_ref().unwrap();
+        cred.set_error(keyring_core::Error::NoStorageAccess("user denied".into()));
+
+        let system = mock_store_with(&[("test-app", "api_key", "system-secret")]);
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            keyrings: vec![Keyring::User, Keyring::System],
+            continue_on_denied,
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "api_key").with_entry_factory(
+            mock_keyrings_factory(vec![(Keyring::User, user), (Keyring::System, system)]),
+        )
+    }
+
+    #[test]
+    fn test_continue_on_denied() {
+        let data = denied_provider(true).data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["api_key"].as_str(),
+            Some("system-secret")
+        );
+    }
+
+    #[test]
+    fn test_denied_aborts_by_default() {
+        let err = denied_provider(false).data().unwrap_err();
+        assert!(err.to_string().starts_with("permission denied:"));
+    }
+
+    #[test]
+    fn test_keyring_provider_config_provenance() {
+        use figment2::providers::Named;
+
+        let config_figment = Figment::new()
+            .merge(Named::new(
+                "config file",
+                Serialized::default("service", "test-app"),
+            ))
+            .merge(Named::new(
+                "cli arguments",
+                Serialized::default("keyrings", vec!["user"]),
+            ));
+        let provider = KeyringProvider::configured_by(config_figment, "api_key");
+
+        let provenance: HashMap<String, String> =
+            provider.config_provenance().unwrap().into_iter().collect();
+        assert!(provenance["service"].starts_with("config file"));
+        assert!(provenance["keyrings"].starts_with("cli arguments"));
+        assert_eq!(provenance["optional"], "default");
+    }
+
+    fn read_only_provider(read_only: bool) -> KeyringProvider {
+        let store = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            read_only,
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(mock_factory(store))
+    }
+
+    #[test]
+    fn test_read_only_blocks_writes() {
+        let provider = read_only_provider(true);
+
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("s3cret"));
+
+        for result in [provider.store("new"), provider.delete()] {
+            assert!(matches!(
+                result,
+                Err(KeyringError::PermissionDenied(msg)) if msg == "keyring is read-only"
+            ));
+        }
+        assert!(provider.exists().unwrap());
+    }
+
+    #[test]
+    fn test_keyring_provider_delete() {
+        let provider = read_only_provider(false);
+        provider.delete().unwrap();
+        assert!(!provider.exists().unwrap());
+        assert!(matches!(provider.delete(), Err(KeyringError::NotFound(_))));
+    }
+
+    fn roles_provider(
+        keyrings: &[&str],
+        roles: &[(&str, KeyringRole)],
+        stores: Vec<(Keyring, Arc<mock::Store>)>,
+    ) -> KeyringProvider {
+        let config_figment = Figment::from(Serialized::defaults(KeyringConfig {
+            service: "test-app".into(),
+            keyrings: keyrings.iter().map(|k| Keyring::from(*k)).collect(),
+            keyring_roles: roles
+                .iter()
+                .map(|(keyring, role)| (Keyring::from(*keyring), *role))
+                .collect(),
+            ..Default::default()
+        }));
+        KeyringProvider::configured_by(config_figment, "api_key")
+            .with_entry_factory(mock_keyrings_factory(stores))
+    }
+
+    #[test]
+    fn test_write_only_keyring_not_read() {
+        let backup = mock_store_with(&[("test-app", "api_key", "stale")]);
+        let user = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = roles_provider(
+            &["backup", "user"],
+            &[("backup", KeyringRole::Write)],
+            vec![
+                (Keyring::from("backup"), backup.clone()),
+                (Keyring::User, user),
+            ],
+        );
+
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default]["api_key"].as_str(), Some("s3cret"));
+
+        provider.store("rotated").unwrap();
+        let entry = backup.build("test-app", "api_key", None).unwrap();
+        assert_eq!(entry.get_password().unwrap(), "rotated");
+        assert_eq!(provider.secret().unwrap(), Some("s3cret".into()));
+    }
+
+    #[test]
+    fn test_read_only_keyring_rejects_writes() {
+        let user = mock_store_with(&[("test-app", "api_key", "s3cret")]);
+        let provider = roles_provider(
+            &["user"],
+            &[("user", KeyringRole::Read)],
+            vec![(Keyring::User, user.clone())],
+        );
+        assert_eq!(provider.secret().unwrap(), Some("s3cret".into()));
+        for result in [provider.store("new"), provider.delete()] {
+            assert!(matches!(
+                result,
+                Err(KeyringError::PermissionDenied(msg)) if msg == "user keyring is read-only"
+            ));
+        }
+
+        let system = mock::Store::new().unwrap();
+        let provider = roles_provider(
+            &["user", "system"],
+            &[("user", KeyringRole::Read)],
+            vec![
+                (Keyring::User, user.clone()),
+                (Keyring::System, system.clone()),
+            ],
+        );
+        provider.store("new").unwrap();
+        let entry = system.build("test-app", "api_key", None).unwrap();
+        assert_eq!(entry.get_password().unwrap(), "new");
+        let entry = user.build("test-app", "api_key", None).unwrap();
+        assert_eq!(entry.get_password().unwrap(), "s3cret");
+    }
+
+    #[test]
+    fn test_keyring_provider_namespaced_by_service() {
+        let store = mock_store_with(&[
+            ("app-one", "api_key", "one-secret"),
+            ("app-two", "api_key", "two-secret"),
+        ]);
+        let figment = Figment::new()
+            .merge(
+                KeyringProvider::new("app-one", "api_key")
+                    .with_entry_factory(mock_factory(store.clone()))
+                    .namespaced_by_service(),
+            )
+            .merge(
+                KeyringProvider::new("app-two", "api_key")
+                    .with_entry_factory(mock_factory(store))
+                    .namespaced_by_service(),
+            );
+
+        let one: String = figment.extract_inner("app-one.api_key").unwrap();
+        let two: String = figment.extract_inner("app-two.api_key").unwrap();
+        assert_eq!(one, "one-secret");
+        assert_eq!(two, "two-secret");
+    }
+
+    #[test]
+    fn test_keyring_provider_with_rotation_falls_back_to_old_name() {
+        let store = mock_store_with(&[("test-app", "api_key_v1", "old-secret")]);
+        let provider = KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(mock_factory(store.clone()))
+            .with_rotation("api_key_v2", "api_key_v1");
+
+        let data = provider.data().unwrap();
+        assert_eq!(
+            data[&Profile::Default]["api_key_v2"].as_str(),
+            Some("old-secret")
+        );
+        assert_eq!(
+            provider.resolve().unwrap(),
+            Some(Resolution {
+                keyring: Keyring::User,
+                credential: "api_key_v1".into(),
+                fallback: true,
+            })
+        );
+
+        store
+            .build("test-app", "api_key_v2", None)
+            .unwrap()
+            .set_password("new-secret")
+            .unwrap();
+        let resolution = provider.resolve().unwrap().unwrap();
+        assert_eq!(resolution.credential, "api_key_v2");
+        assert!(!resolution.fallback);
+    }
+
+    #[test]
+    fn test_keyring_provider_merge_json_parts() {
+        let store = mock_store_with(&[
+            (
+                "test-app",
+                "cfg.part1",
+                r#"{"database": {"host": "db1", "port": 5432}, "region": "us"}"#,
+            ),
+            (
+                "test-app",
+                "cfg.part2",
+                r#"{"database": {"host": "db2"}, "debug": true}"#,
+            ),
+        ]);
+        let provider = KeyringProvider::new("test-app", "cfg")
+            .with_entry_factory(mock_factory(store))
+            .merge_json_parts(vec!["cfg.part1", "cfg.part2"]);
+
+        let figment = Figment::new().merge(provider);
+        let host: String = figment.extract_inner("database.host").unwrap();
+        let port: u16 = figment.extract_inner("database.port").unwrap();
+        let region: String = figment.extract_inner("region").unwrap();
+        let debug: bool = figment.extract_inner("debug").unwrap();
+        assert_eq!(host, "db2");
+        assert_eq!(port, 5432);
+        assert_eq!(region, "us");
+        assert!(debug);
+    }
 }
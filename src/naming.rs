@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Naming conventions of other tools that store secrets in the keyring.
+//!
+//! A [`NamingStrategy`] turns the provider's service and credential name
+//! into the service, username and entry modifiers the backend is asked for,
+//! so secrets written by those tools can be read as they are.
+
+use std::collections::HashMap;
+
+/// Service, username and entry modifiers passed to the backend.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BackendNames {
+    /// Service of the keyring entry
+    pub service: String,
+    /// Username of the keyring entry
+    pub username: String,
+    /// Extra entry modifiers, such as `target`
+    pub modifiers: HashMap<String, String>,
+}
+
+/// Maps a provider's `(service, credential)` to backend names, see
+/// [`KeyringProvider::with_naming`](crate::KeyringProvider::with_naming).
+pub trait NamingStrategy: Send + Sync {
+    /// Backend names for `credential` under `service`.
+    fn backend_names(&self, service: &str, credential: &str) -> BackendNames;
+}
+
+/// Use the service and credential name as they are (the default).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Verbatim;
+
+impl NamingStrategy for Verbatim {
+    fn backend_names(&self, service: &str, credential: &str) -> BackendNames {
+        BackendNames {
+            service: service.into(),
+            username: credential.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// GitHub CLI tokens, stored under `gh:{host}` for each account.
+///
+/// The provider's service is the host, such as `github.com`, and the
+/// credential is the account name.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GhCli;
+
+impl NamingStrategy for GhCli {
+    fn backend_names(&self, service: &str, credential: &str) -> BackendNames {
+        BackendNames {
+            service: format!("gh:{}", service),
+            username: credential.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Git Credential Manager credentials, stored under `git:{url}` for each
+/// account.
+///
+/// The provider's service is the remote URL, such as
+/// `https://github.com`, and the credential is the account name.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GitCredentialManager;
+
+impl NamingStrategy for GitCredentialManager {
+    fn backend_names(&self, service: &str, credential: &str) -> BackendNames {
+        BackendNames {
+            service: format!("git:{}", service),
+            username: credential.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// aws-vault credentials, stored per profile in aws-vault's own keychain
+/// or collection.
+///
+/// The credential is the AWS profile name; the provider's service is not
+/// used.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AwsVault;
+
+impl NamingStrategy for AwsVault {
+    fn backend_names(&self, _service: &str, credential: &str) -> BackendNames {
+        let target = if cfg!(target_os = "macos") {
+            "aws-vault"
+        } else {
+            "awsvault"
+        };
+        BackendNames {
+            service: "aws-vault".into(),
+            username: credential.into(),
+            modifiers: HashMap::from([("target".into(), target.into())]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gh_cli_names() {
+        assert_eq!(
+            GhCli.backend_names("github.com", "octocat"),
+            BackendNames {
+                service: "gh:github.com".into(),
+                username: "octocat".into(),
+                modifiers: HashMap::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_aws_vault_names() {
+        let names = AwsVault.backend_names("ignored", "prod");
+        assert_eq!(names.service, "aws-vault");
+        assert_eq!(names.username, "prod");
+        assert!(names.modifiers.contains_key("target"));
+    }
+}
@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Secret values that stay out of panic messages and logs.
+
+use crate::redact_figment_error;
+use figment2::{Error, Figment};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+
+/// A string whose `Debug` and `Display` output is `[redacted]`.
+///
+/// Use it for secret fields of config structs, so that printing the struct,
+/// or a panic that does, never shows the secret. The value is read with
+/// [`expose_secret`](Self::expose_secret).
+///
+/// ```rust
+/// # use figment_keyring::SecretString;
+/// let secret = SecretString::from("s3cret".to_string());
+/// assert_eq!(format!("{:?}", secret), "[redacted]");
+/// assert_eq!(secret.expose_secret(), "s3cret");
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// The secret itself.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl std::fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+impl std::fmt::Display for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+/// [`Figment::extract`] with keyring values scrubbed from the error, see
+/// [`redact_figment_error`].
+///
+/// Plain `String` fields that fail to deserialize would otherwise have their
+/// value quoted in the error, and so in the panic message of an `unwrap`.
+#[allow(clippy::result_large_err)]
+pub fn extract_redacted<T: DeserializeOwned>(figment: &Figment) -> Result<T, Error> {
+    figment.extract().map_err(redact_figment_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment2::providers::Serialized;
+    use keyring_core::api::CredentialStoreApi;
+
+    fn panic_message(f: impl FnOnce()) -> String {
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_err();
+        match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => payload.downcast_ref::<&str>().unwrap().to_string(),
+        }
+    }
+
+    #[test]
+    fn test_secret_string_panic_is_redacted() {
+        #[derive(Debug, Deserialize)]
+        struct Config {
+            api_key: SecretString,
+        }
+
+        let figment = Figment::from(Serialized::default("api_key", "s3cret-text"));
+        let config: Config = figment.extract().unwrap();
+        assert_eq!(config.api_key.expose_secret(), "s3cret-text");
+        let message = panic_message(|| panic!("bad config: {:?}", config));
+        assert!(message.contains("[redacted]"));
+        assert!(!message.contains("s3cret-text"));
+    }
+
+    #[test]
+    fn test_extract_redacted_panic_is_redacted() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Config {
+            api_key: u32,
+        }
+
+        let store = keyring_core::mock::Store::new().unwrap();
+        store
+            .build("test-app", "api_key", None)
+            .unwrap()
+            .set_password("s3cret-text")
+            .unwrap();
+        let provider = crate::KeyringProvider::new("test-app", "api_key")
+            .with_entry_factory(crate::tests::mock_factory(store));
+        let figment = Figment::from(provider);
+
+        let unredacted = panic_message(|| {
+            figment.extract::<Config>().unwrap();
+        });
+        assert!(unredacted.contains("s3cret-text"));
+        let message = panic_message(|| {
+            extract_redacted::<Config>(&figment).unwrap();
+        });
+        assert!(message.contains("api_key"));
+        assert!(!message.contains("s3cret-text"));
+    }
+}
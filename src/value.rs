@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! The value a secret resolves to, before it is converted for figment.
+
+use figment2::value::{Dict, Value};
+
+/// A resolved secret.
+///
+/// Reading and transforming a secret produces one of these; only
+/// [`insert_into`](Self::insert_into) and the `From` impl turn it into
+/// figment values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyringValue {
+    /// A UTF-8 secret
+    String(String),
+    /// A secret that is not valid UTF-8, kept as raw bytes
+    Bytes(Vec<u8>),
+    /// A secret split on the list delimiter
+    List(Vec<String>),
+    /// A secret parsed into a structured value, such as a coerced number
+    Parsed(Value),
+    /// A dotenv secret, each entry emitted under its own key
+    Entries(Vec<(String, String)>),
+}
+
+impl KeyringValue {
+    /// Insert the value into `dict` under `key`.
+    ///
+    /// [`Entries`](Self::Entries) are inserted under their own names
+    /// instead.
+    pub fn insert_into(self, dict: &mut Dict, key: &str) {
+        match self {
+            KeyringValue::Entries(entries) => {
+                for (name, value) in entries {
+                    dict.insert(name, Value::from(value));
+                }
+            }
+            value => {
+                dict.insert(key.to_string(), Value::from(value));
+            }
+        }
+    }
+}
+
+impl From<KeyringValue> for Value {
+    fn from(value: KeyringValue) -> Self {
+        match value {
+            KeyringValue::String(secret) => Value::from(secret),
+            KeyringValue::Bytes(secret) => Value::from(secret),
+            KeyringValue::List(parts) => Value::from(parts),
+            KeyringValue::Parsed(value) => value,
+            KeyringValue::Entries(entries) => Value::from(
+                entries
+                    .into_iter()
+                    .map(|(name, value)| (name, Value::from(value)))
+                    .collect::<Dict>(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_value() {
+        assert_eq!(
+            Value::from(KeyringValue::String("s3cret".into())),
+            Value::from("s3cret")
+        );
+        assert_eq!(
+            Value::from(KeyringValue::Bytes(vec![0xff, 0x01])),
+            Value::from(vec![255u8, 1])
+        );
+        assert_eq!(
+            Value::from(KeyringValue::List(vec!["a".into(), "b".into()])),
+            Value::from(vec!["a", "b"])
+        );
+        assert_eq!(
+            Value::from(KeyringValue::Parsed(Value::from(8443))),
+            Value::from(8443)
+        );
+        assert_eq!(
+            Value::from(KeyringValue::Entries(vec![("USER".into(), "admin".into())])),
+            Value::from(Dict::from([("USER".to_string(), Value::from("admin"))]))
+        );
+    }
+
+    #[test]
+    fn test_insert_into() {
+        let mut dict = Dict::new();
+        KeyringValue::String("s3cret".into()).insert_into(&mut dict, "api_key");
+        KeyringValue::Entries(vec![
+            ("USER".into(), "admin".into()),
+            ("PASS".into(), "hunter2".into()),
+        ])
+        .insert_into(&mut dict, "env");
+        assert_eq!(dict.get("api_key"), Some(&Value::from("s3cret")));
+        assert_eq!(dict.get("USER"), Some(&Value::from("admin")));
+        assert_eq!(dict.get("PASS"), Some(&Value::from("hunter2")));
+        assert!(!dict.contains_key("env"));
+    }
+}